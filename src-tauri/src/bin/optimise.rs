@@ -0,0 +1,273 @@
+//! Offline search for the strongest bot personality, using a genetic algorithm
+//! scored by simulated games against the built in bots.
+//!
+//! ```sh
+//! cargo run --release --bin optimise -- --generations 30 --out expert.json
+//! ```
+//!
+//! The output is a personality JSON file, which can be played in the tournament
+//! or saved in the app as a custom personality.
+use std::{env, process::ExitCode, thread};
+
+use anyhow::{anyhow, Context as _};
+use cant_hop_lib::state::{MatchupReport, Personality, PlayerMode};
+use rand::{rngs::StdRng, seq::IndexedRandom as _, Rng, SeedableRng};
+
+const USAGE: &str = "\
+Usage: optimise [OPTIONS]
+
+Searches bot personality parameters for the highest win rate against the
+Safe, Normal and Risky bots, then writes the best one to a JSON file.
+
+Options:
+  --population <N>   Candidates per generation [default: 24]
+  --generations <N>  Generations to evolve [default: 20]
+  --games <N>        Seeds played per opponent for each candidate [default: 100]
+  --seed <N>         Seed for the search itself [default: 0]
+  --out <PATH>       Where to write the best personality [default: personality.json]
+  -h, --help         Print this help";
+
+/// Number of parameters in a personality.
+const GENES: usize = 9;
+/// Lowest and highest value searched for each parameter, in `to_genes` order.
+const BOUNDS: [(f64, f64); GENES] = [
+    (0.0, 10.0), // weight_active
+    (0.0, 5.0),  // weight_progress
+    (0.0, 5.0),  // weight_distance
+    (0.0, 5.0),  // weight_opponent
+    (0.0, 5.0),  // weight_rival
+    (0.0, 3.0),  // weight_probability
+    (0.05, 0.9), // risk_threshold
+    (0.0, 0.15), // risk_aversion_per_hop
+    (0.0, 0.4),  // minimum_risk_threshold
+];
+/// Best candidates carried into the next generation unchanged.
+const ELITES: usize = 2;
+/// Candidates compared when picking each parent.
+const TOURNAMENT_SIZE: usize = 3;
+/// Chance of each parameter being mutated in a child.
+const MUTATION_RATE: f64 = 0.25;
+/// Size of a mutation, as a fraction of the parameter's range.
+const MUTATION_SCALE: f64 = 0.1;
+/// Games per opponent in the final evaluation, as a multiple of `--games`. They're played on
+/// the seeds after the last generation's, so the result isn't overfit to the training dice.
+const VALIDATION_ROUNDS: u64 = 5;
+
+struct Args {
+    population: usize,
+    generations: usize,
+    games: u64,
+    seed: u64,
+    out: String,
+}
+
+type Genes = [f64; GENES];
+
+fn to_genes(p: &Personality) -> Genes {
+    [
+        p.weight_active,
+        p.weight_progress,
+        p.weight_distance,
+        p.weight_opponent,
+        p.weight_rival,
+        p.weight_probability,
+        p.risk_threshold,
+        p.risk_aversion_per_hop,
+        p.minimum_risk_threshold,
+    ]
+}
+
+fn from_genes(g: &Genes) -> Personality {
+    Personality {
+        weight_active: g[0],
+        weight_progress: g[1],
+        weight_distance: g[2],
+        weight_opponent: g[3],
+        weight_rival: g[4],
+        weight_probability: g[5],
+        risk_threshold: g[6],
+        risk_aversion_per_hop: g[7],
+        minimum_risk_threshold: g[8],
+    }
+}
+
+fn parse_args() -> anyhow::Result<Option<Args>> {
+    let mut args = Args {
+        population: 24,
+        generations: 20,
+        games: 100,
+        seed: 0,
+        out: "personality.json".to_string(),
+    };
+    let mut argv = env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(None);
+        }
+        let value = argv
+            .next()
+            .with_context(|| format!("{arg} needs a value"))?;
+        let number = || {
+            value
+                .parse::<u64>()
+                .with_context(|| format!("{arg} should be a number"))
+        };
+        match arg.as_str() {
+            "--population" => args.population = number()? as usize,
+            "--generations" => args.generations = number()? as usize,
+            "--games" => args.games = number()?,
+            "--seed" => args.seed = number()?,
+            "--out" => args.out = value,
+            _ => return Err(anyhow!("unknown option '{arg}'")),
+        }
+    }
+    if args.population <= ELITES {
+        return Err(anyhow!("--population must be more than {ELITES}"));
+    }
+    if args.generations == 0 {
+        return Err(anyhow!("--generations must be at least 1"));
+    }
+    if args.games == 0 {
+        return Err(anyhow!("--games must be at least 1"));
+    }
+    (args.generations as u64 + VALIDATION_ROUNDS)
+        .checked_mul(args.games)
+        .context("--generations and --games are too large to seed every game")?;
+    Ok(Some(args))
+}
+
+/// Average win rate of the personality heads up against each of the built in bots.
+fn fitness(personality: Personality, seeds: std::ops::Range<u64>) -> f64 {
+    let opponents = [PlayerMode::Safe, PlayerMode::Normal, PlayerMode::Risky];
+    let total: f64 = opponents
+        .iter()
+        .map(|&opponent| {
+            let lineup = [PlayerMode::Custom(personality), opponent];
+            MatchupReport::play(&lineup, seeds.clone(), 3)
+                .expect("bots only lineup")
+                .win_rate(0)
+                .value
+        })
+        .sum();
+    total / opponents.len() as f64
+}
+
+/// Score every candidate, spread across the available cores.
+fn score(population: &[Genes], seeds: std::ops::Range<u64>) -> Vec<f64> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = population.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = population
+            .chunks(chunk)
+            .map(|chunk| {
+                let seeds = seeds.clone();
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|genes| fitness(from_genes(genes), seeds.clone()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("scoring thread panicked"))
+            .collect()
+    })
+}
+
+/// Pick the fittest of a few random candidates.
+fn select<'a>(scored: &'a [(f64, Genes)], rng: &mut StdRng) -> &'a Genes {
+    let mut best = scored.choose(rng).expect("population is not empty");
+    for _ in 1..TOURNAMENT_SIZE {
+        let other = scored.choose(rng).expect("population is not empty");
+        if other.0 > best.0 {
+            best = other;
+        }
+    }
+    &best.1
+}
+
+/// Blend two parents and mutate the result.
+fn breed(a: &Genes, b: &Genes, rng: &mut StdRng) -> Genes {
+    let mut child = [0.0; GENES];
+    for (gene, (low, high)) in BOUNDS.iter().enumerate() {
+        let mix: f64 = rng.random();
+        let mut value = a[gene] * mix + b[gene] * (1.0 - mix);
+        if rng.random_bool(MUTATION_RATE) {
+            let range = high - low;
+            value += (rng.random::<f64>() * 2.0 - 1.0) * range * MUTATION_SCALE;
+        }
+        child[gene] = value.clamp(*low, *high);
+    }
+    child
+}
+
+fn optimise(args: &Args) -> Personality {
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    // Start from the built in bots, filled out with random candidates.
+    let mut population: Vec<Genes> = [Personality::SAFE, Personality::NORMAL, Personality::RISKY]
+        .iter()
+        .map(to_genes)
+        .collect();
+    while population.len() < args.population {
+        population.push(BOUNDS.map(|(low, high)| rng.random_range(low..=high)));
+    }
+    population.truncate(args.population);
+
+    let mut best = (f64::MIN, population[0]);
+    for generation in 0..args.generations as u64 {
+        // Fresh dice each generation, shared by every candidate so they are compared fairly.
+        let start = generation * args.games;
+        let fitness = score(&population, start..start + args.games);
+        let mut scored: Vec<(f64, Genes)> = fitness.into_iter().zip(population).collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        best = scored[0];
+        println!(
+            "generation {:3}: best {:5.1}% mean {:5.1}%",
+            generation + 1,
+            best.0 * 100.0,
+            scored.iter().map(|(f, _)| f).sum::<f64>() / scored.len() as f64 * 100.0
+        );
+
+        population = scored
+            .iter()
+            .take(ELITES)
+            .map(|(_, genes)| *genes)
+            .collect();
+        while population.len() < args.population {
+            let a = select(&scored, &mut rng);
+            let b = select(&scored, &mut rng);
+            population.push(breed(a, b, &mut rng));
+        }
+    }
+    from_genes(&best.1)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e:#}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let personality = optimise(&args);
+    let start = args.generations as u64 * args.games;
+    let validation = start..start + args.games * VALIDATION_ROUNDS;
+    println!(
+        "validation: {:.1}% average win rate against Safe, Normal and Risky",
+        fitness(personality, validation) * 100.0
+    );
+    let json = serde_json::to_string_pretty(&personality).expect("personality is serialisable");
+    if let Err(e) = std::fs::write(&args.out, json) {
+        eprintln!("error: failed to write {}: {e}", args.out);
+        return ExitCode::FAILURE;
+    }
+    println!("wrote {}", args.out);
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,154 @@
+//! Solve a reduced two player board perfectly, writing the table of values to a file.
+//!
+//! ```sh
+//! cargo run --release --bin solve -- --columns 6,7,8 --length 2 --win-cols 2 --out short.bin
+//! cargo run --release --bin solve -- --table short.bin --play 1000
+//! ```
+use std::{env, path::PathBuf, process::ExitCode, time::Instant};
+
+use anyhow::{anyhow, Context as _};
+use cant_hop_lib::state::{PlayerMode, SolvedVariant, Variant};
+
+const USAGE: &str = "\
+Usage: solve [OPTIONS]
+
+Solves a reduced two player board, where both players start a few hops from
+the top of a few columns and the rest are closed, and reports the first
+player's chance of winning with perfect play. The perfect player can then
+play the bots on the board.
+
+Options:
+  --columns <COLS>  Comma separated columns in play, 1 to 4 of 2-12 [default: 6,7,8]
+  --length <N>      Hops to the top of each column [default: 2]
+  --win-cols <N>    Columns required to win [default: 2]
+  --out <PATH>      Write the solved table to a file
+  --table <PATH>    Load a solved table written with --out, instead of solving
+  --play <N>        Play N games against each bot, half of them going first
+  -h, --help        Print this help";
+
+struct Args {
+    columns: Vec<usize>,
+    length: usize,
+    win_cols: usize,
+    out: Option<PathBuf>,
+    table: Option<PathBuf>,
+    play: usize,
+}
+
+fn parse_args() -> anyhow::Result<Option<Args>> {
+    let mut args = Args {
+        columns: vec![6, 7, 8],
+        length: 2,
+        win_cols: 2,
+        out: None,
+        table: None,
+        play: 0,
+    };
+    let mut argv = env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = |name: &str| argv.next().with_context(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--columns" => {
+                args.columns = value("--columns")?
+                    .split(',')
+                    .map(|col| col.parse().context("--columns should be numbers"))
+                    .collect::<anyhow::Result<_>>()?;
+            }
+            "--length" => {
+                args.length = value("--length")?
+                    .parse()
+                    .context("--length should be a number")?;
+            }
+            "--win-cols" => {
+                args.win_cols = value("--win-cols")?
+                    .parse()
+                    .context("--win-cols should be a number")?;
+            }
+            "--out" => args.out = Some(value("--out")?.into()),
+            "--table" => args.table = Some(value("--table")?.into()),
+            "--play" => {
+                args.play = value("--play")?
+                    .parse()
+                    .context("--play should be a number")?;
+            }
+            other => return Err(anyhow!("unexpected argument '{other}'")),
+        }
+    }
+    Ok(Some(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let solved = match &args.table {
+        Some(table) => {
+            let solved = SolvedVariant::read(table)?;
+            println!("loaded {}", table.display());
+            solved
+        }
+        None => solve(Variant::new(args.columns, args.length, args.win_cols)?)?,
+    };
+    let variant = &solved.variant;
+    println!(
+        "columns {:?}, {} hops each, first to {} columns: first player wins {:.2}% with perfect play",
+        variant.columns,
+        variant.length,
+        variant.win_cols,
+        solved.first_player_wins() * 100.0
+    );
+    if let Some(out) = args.out {
+        solved.write(&out)?;
+        println!("wrote {}", out.display());
+    }
+    for bot in [PlayerMode::Safe, PlayerMode::Normal, PlayerMode::Risky] {
+        if args.play == 0 {
+            break;
+        }
+        let mut won = 0;
+        for game in 0..args.play {
+            let player = game % 2;
+            let winner = solved.play_against(bot, player, game as u64)?;
+            won += (winner == Some(player)) as usize;
+        }
+        println!(
+            "perfect player beat the {bot} bot in {won} of {} games ({:.1}%)",
+            args.play,
+            won as f64 * 100.0 / args.play as f64
+        );
+    }
+    Ok(())
+}
+
+fn solve(variant: Variant) -> anyhow::Result<SolvedVariant> {
+    println!(
+        "solving columns {:?}, {} hops each, first to {} columns ({} positions)",
+        variant.columns,
+        variant.length,
+        variant.win_cols,
+        variant.positions()
+    );
+    let start = Instant::now();
+    let (solved, sweeps) = SolvedVariant::solve(variant)?;
+    println!("settled after {} sweeps in {:.1?}", sweeps, start.elapsed());
+    Ok(solved)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e:#}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
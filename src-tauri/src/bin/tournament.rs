@@ -0,0 +1,156 @@
+//! Headless bot-vs-bot tournament, for measuring changes to the bot logic.
+//!
+//! ```sh
+//! cargo run --release --bin tournament -- safe,risky normal,normal,risky --seeds 0..500
+//! ```
+use std::{env, ops::Range, process::ExitCode};
+
+use anyhow::{anyhow, Context as _};
+use cant_hop_lib::state::{Estimate, MatchupReport, Personality, PlayerMode};
+
+const USAGE: &str = "\
+Usage: tournament [OPTIONS] <LINEUP>...
+
+Plays every seating of each lineup once per seed and reports the results.
+
+Arguments:
+  <LINEUP>  Comma separated bots, 2 to 4 of: safe, normal, risky, adaptive,
+            or a path to a personality JSON file.
+
+Options:
+  --seeds <START>..<END>  Range of dice seeds to play [default: 0..1000]
+  --win-cols <N>          Columns required to win, 1 to 11 [default: 3]
+  -h, --help              Print this help";
+
+struct Args {
+    lineups: Vec<Vec<PlayerMode>>,
+    seeds: Range<u64>,
+    win_cols: usize,
+}
+
+/// Parse a bot from its name, or from a personality file.
+fn parse_mode(value: &str) -> anyhow::Result<PlayerMode> {
+    match value.to_lowercase().as_str() {
+        "safe" => Ok(PlayerMode::Safe),
+        "normal" => Ok(PlayerMode::Normal),
+        "risky" => Ok(PlayerMode::Risky),
+        "adaptive" => Ok(PlayerMode::Adaptive),
+        "human" => Err(anyhow!("Human players can't take part in a tournament")),
+        _ => {
+            let file = std::fs::read_to_string(value)
+                .with_context(|| format!("'{value}' is not a bot or a readable file"))?;
+            let personality: Personality = serde_json::from_str(&file)
+                .with_context(|| format!("'{value}' is not a valid personality"))?;
+            Ok(PlayerMode::Custom(personality))
+        }
+    }
+}
+
+fn parse_seeds(value: &str) -> anyhow::Result<Range<u64>> {
+    let (start, end) = value
+        .split_once("..")
+        .with_context(|| format!("seeds should be a range like 0..1000, got '{value}'"))?;
+    let seeds = start.parse()?..end.parse()?;
+    if seeds.is_empty() {
+        return Err(anyhow!("seed range {value} is empty"));
+    }
+    Ok(seeds)
+}
+
+fn parse_args() -> anyhow::Result<Option<Args>> {
+    let mut args = Args {
+        lineups: vec![],
+        seeds: 0..1000,
+        win_cols: 3,
+    };
+    let mut argv = env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--seeds" => {
+                let value = argv.next().context("--seeds needs a value")?;
+                args.seeds = parse_seeds(&value)?;
+            }
+            "--win-cols" => {
+                let value = argv.next().context("--win-cols needs a value")?;
+                args.win_cols = value.parse().context("--win-cols should be a number")?;
+                // there are 11 columns, from 2 to 12
+                if !(1..=11).contains(&args.win_cols) {
+                    return Err(anyhow!("--win-cols should be 1 to 11, got {value}"));
+                }
+            }
+            lineup => {
+                let modes = lineup
+                    .split(',')
+                    .map(parse_mode)
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                if !(2..=4).contains(&modes.len()) {
+                    return Err(anyhow!("lineup '{lineup}' should have 2 to 4 bots"));
+                }
+                args.lineups.push(modes);
+            }
+        }
+    }
+    if args.lineups.is_empty() {
+        return Err(anyhow!("at least one lineup is required"));
+    }
+    Ok(Some(args))
+}
+
+fn percent(estimate: Estimate) -> String {
+    format!(
+        "{:5.1}% [{:5.1}% - {:5.1}%]",
+        estimate.value * 100.0,
+        estimate.low * 100.0,
+        estimate.high * 100.0
+    )
+}
+
+fn print_report(report: &MatchupReport) {
+    let names: Vec<String> = report.lineup.iter().map(|mode| mode.to_string()).collect();
+    println!("== {} ==", names.join(" vs "));
+    println!("games: {} ({} unfinished)", report.games, report.unfinished);
+    for (entry, name) in names.iter().enumerate() {
+        println!(
+            "  {} {:<8} wins {}",
+            entry + 1,
+            name,
+            percent(report.win_rate(entry))
+        );
+    }
+    let length = report.game_length();
+    println!(
+        "  game length {:.1} runs [{:.1} - {:.1}]",
+        length.value, length.low, length.high
+    );
+    println!(
+        "  first player wins {} (fair {:.1}%)",
+        percent(report.first_player_win_rate()),
+        100.0 / report.lineup.len() as f64
+    );
+    println!();
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e:#}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for lineup in &args.lineups {
+        match MatchupReport::play(lineup, args.seeds.clone(), args.win_cols) {
+            Ok(report) => print_report(&report),
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
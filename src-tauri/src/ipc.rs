@@ -1,40 +1,89 @@
+pub mod achievements;
 pub mod ai;
+pub mod career;
+pub mod forecast;
+pub mod profiles;
+pub mod ratings;
+pub mod records;
+pub mod replays;
+pub mod saves;
 
-use anyhow::anyhow;
-use rand::random;
+use std::sync::{atomic::Ordering, Mutex};
+
+use tauri::Manager as _;
 
 use crate::{
     state::{
-        evaluate_moves, AppContext, ColumnID, DiceResult, GameState, SettingsState, StatsSummary,
+        evaluate_moves, preview_choices, throw_dice, AdaptiveRecord, AppContext, Choice, ColumnID,
+        DiceResult, Feedback, GameState, Objective, OpponentModels, OptimalPlay, PlayerMode,
+        PlayerReview, RollAnalysis, RunOutlook, SettingsState, SolverCache, StatsSummary,
+        WinChances, DEFAULT_PLAYOUTS,
     },
     utils::{generate_name, get_store},
 };
 
+/// Mark the game as swapped for another, so a bot's run playing in the background stops.
+/// Called while holding the game.
+fn replace_game(state: &AppContext) {
+    state.generation.fetch_add(1, Ordering::SeqCst);
+}
+
 #[tauri::command]
 /// Initialize the GameState and Game History data from disk.
+/// Errors if either couldn't be loaded, after backing it up and starting afresh.
 pub fn init_store(state: tauri::State<AppContext>, app: tauri::AppHandle) -> tauri::Result<()> {
     let mut game_state = state.game.lock().unwrap();
     let mut history = state.hist.lock().unwrap();
+    replace_game(&state);
     let store = get_store(&app)?;
-    game_state.update_from_store(&store);
-    history.update_from_store(&store);
+    let state_loaded = game_state.update_from_store(&store);
+    let history_loaded = history.update_from_store(&store);
+    state_loaded?;
+    history_loaded?;
     Ok(())
 }
 
 #[tauri::command]
-/// On Settings page, when starting a new game.
+/// On Settings page, when starting a new game. The game gets its own save slot,
+/// with the given name or named after its players, keeping any game being played in its own.
 pub fn start_game(
     settings: SettingsState,
+    name: Option<String>,
     state: tauri::State<AppContext>,
     app: tauri::AppHandle,
 ) -> tauri::Result<()> {
     println!("Starting game with settings: {:?}", settings);
     let mut game_state = state.game.lock().unwrap();
     let mut game_history = state.hist.lock().unwrap();
+    replace_game(&state);
+    let store = get_store(&app)?;
+    let mut settings = settings;
+    profiles::link_players(&mut settings.players, &store)?;
+    saves::leave_game(&game_state, &game_history, &store)?;
     game_history.new_game(settings.players.len())?;
     game_state.new_game(settings);
 
-    let store = get_store(&app)?;
+    let mut record = AdaptiveRecord::default();
+    if let Err(e) = record.update_from_store(&store) {
+        // already backed up, carry on from the default skill
+        println!("{:#}", e);
+    }
+    game_state.adaptive_skill = record.skill();
+    let mut models = OpponentModels::default();
+    if let Err(e) = models.update_from_store(&store) {
+        // already backed up, the bots start learning afresh
+        println!("{:#}", e);
+    }
+    game_state.opponent_models = game_state
+        .settings
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(_, player)| matches!(player.mode, PlayerMode::Human))
+        .filter_map(|(idx, player)| Some((idx, models.0.get(player.key())?.clone())))
+        .collect();
+    saves::start_slot(&game_state, &game_history, name, &store)?;
+    forecast::extend_timeline(&game_state, &game_history, &state, &app, &store);
     game_state.write_to_store(&store)?;
     game_history.write_to_store(&store)?;
     Ok(())
@@ -63,15 +112,28 @@ pub fn get_game_statistics(state: tauri::State<AppContext>) -> StatsSummary {
     history.calculate_summary()
 }
 
+#[tauri::command]
+/// Review every player's decisions against the solver's. Each run is a new board
+/// to solve, which takes a few seconds, so it's done on a blocking thread.
+pub async fn get_game_review(
+    state: tauri::State<'_, AppContext>,
+) -> tauri::Result<Vec<PlayerReview>> {
+    let history = state.hist.lock().unwrap().clone();
+    tauri::async_runtime::spawn_blocking(move || history.review()).await
+}
+
 #[tauri::command]
 /// Game over, reset the gamestate ready for a new game.
 pub fn stop_game(state: tauri::State<AppContext>, app: tauri::AppHandle) -> tauri::Result<()> {
     let mut game_state = state.game.lock().unwrap();
     let mut history = state.hist.lock().unwrap();
-    game_state.clear();
-    history.clear();
+    replace_game(&state);
     {
         let store = get_store(&app)?;
+        // leave the game as it ended in its save slot
+        saves::leave_game(&game_state, &history, &store)?;
+        game_state.clear();
+        history.clear();
         game_state.write_to_store(&store)?;
         history.write_to_store(&store)?;
     }
@@ -79,6 +141,65 @@ pub fn stop_game(state: tauri::State<AppContext>, app: tauri::AppHandle) -> taur
     Ok(())
 }
 
+#[tauri::command]
+/// Return what the bots have learnt about each human, by profile id or name.
+pub fn get_opponent_models(app: tauri::AppHandle) -> tauri::Result<OpponentModels> {
+    let store = get_store(&app)?;
+    let mut models = OpponentModels::default();
+    models.update_from_store(&store)?;
+    Ok(models)
+}
+
+#[tauri::command]
+/// Return how likely each column is to be offered on the current player's next roll,
+/// and which dice would croak them.
+pub fn get_roll_analysis(state: tauri::State<AppContext>) -> RollAnalysis {
+    let game_state = state.game.lock().unwrap();
+    game_state.analyse_roll()
+}
+
+#[tauri::command]
+/// Return what the current player can expect if they keep rolling for up to `rolls` more rolls.
+/// Looking far ahead takes a while, so this runs off the main thread without holding the game.
+pub async fn get_run_outlook(
+    rolls: usize,
+    state: tauri::State<'_, AppContext>,
+) -> tauri::Result<RunOutlook> {
+    let game_state = state.game.lock().unwrap().clone();
+    Ok(game_state.run_outlook(rolls)?)
+}
+
+#[tauri::command]
+/// Return how the current player should play the rest of their run for the `objective`,
+/// ranking the offered `options` if they have rolled. Boards are solved once, then cached.
+/// Solving takes a while, so it's done on a blocking thread without holding the game.
+pub async fn get_optimal_play(
+    objective: Objective,
+    options: Option<DiceResult>,
+    state: tauri::State<'_, AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<OptimalPlay> {
+    let game_state = state.game.lock().unwrap().clone();
+    let choices: Option<Vec<Choice>> = options.map(|options| options.choices.into_iter().collect());
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppContext>();
+        game_state.optimal_play(objective, choices.as_deref(), &state.solvers)
+    })
+    .await
+}
+
+#[tauri::command]
+/// Return each player's chance of winning from here, estimated by playing the rest
+/// of the game out `playouts` times.
+pub async fn get_win_chances(
+    playouts: Option<usize>,
+    state: tauri::State<'_, AppContext>,
+) -> tauri::Result<WinChances> {
+    let game_state = state.game.lock().unwrap().clone();
+    let playouts = playouts.unwrap_or(DEFAULT_PLAYOUTS);
+    Ok(game_state.win_chances(playouts, rand::random())?)
+}
+
 #[tauri::command]
 /// Return a random name for the player
 pub fn get_name(seed: Option<u64>) -> String {
@@ -93,51 +214,110 @@ pub fn roll_dice(
     state: tauri::State<AppContext>,
     app: tauri::AppHandle,
 ) -> tauri::Result<DiceResult> {
-    const DICE_SIDES: u8 = 6;
-    const DICE_COUNT: usize = 4;
-    let dice = [0; DICE_COUNT].map(|_| (random::<u8>() % DICE_SIDES + 1) as usize);
+    roll(&state, &app)
+}
+
+/// Roll for the current player, recording the result in their history.
+fn roll(state: &AppContext, app: &tauri::AppHandle) -> tauri::Result<DiceResult> {
+    let dice = throw_dice(&mut rand::rng());
     let game_state = state.game.lock().unwrap();
 
     let selected = game_state.get_selected();
     let unavailable = game_state.get_unavailable();
 
     let choices = evaluate_moves(dice, &selected, &unavailable);
-    let result = DiceResult { dice, choices };
+    let mut result = DiceResult {
+        dice,
+        choices,
+        ..Default::default()
+    };
     {
         // update game history record
         let mut history = state.hist.lock().unwrap();
         history.player_mut().record_roll(&result, &selected);
-        let store = get_store(&app)?;
+        let store = get_store(app)?;
         history.write_to_store(&store)?;
     }
+    // The previews are only for the player, so aren't kept in the history.
+    result.previews = preview_choices(&result.choices, &selected, &unavailable);
     Ok(result)
 }
 
 #[tauri::command]
-/// Choose columns to risk
-pub fn choose_columns(
+/// Choose columns to risk. In training mode, the choice is graded against the solver.
+pub async fn choose_columns(
     first: ColumnID,
     second: Option<ColumnID>,
-    state: tauri::State<AppContext>,
+    state: tauri::State<'_, AppContext>,
     app: tauri::AppHandle,
 ) -> tauri::Result<GameState> {
-    println!("choosing columns: {:?} {:?}", first, second);
-    let mut game_state = state.game.lock().unwrap();
-    let Some(col1) = game_state.columns.get_mut(first) else {
-        return Err(anyhow!("Invalid column index {}", first).into());
+    let options = state.hist.lock().unwrap().player().last_roll().cloned();
+    let feedback = match options {
+        Some(options) => {
+            // convert from index to 2d6
+            let chosen = (first + 2, second.map(|x| x + 2));
+            grade_in_training(&state, &app, move |game_state, cache| {
+                game_state.grade_choice(&options.choices, chosen, cache)
+            })
+            .await?
+        }
+        None => None,
     };
-    col1.risked += 1;
-    if let Some(second) = second {
-        let Some(col2) = game_state.columns.get_mut(second) else {
-            return Err(anyhow!("Invalid column index {}", second).into());
-        };
-        col2.risked += 1;
+    risk(first, second, feedback, &state, &app)
+}
+
+/// Whether the current player's decisions are graded, which is only for humans in training mode.
+fn in_training(game_state: &GameState) -> bool {
+    let player = &game_state.settings.players[game_state.current_player];
+    game_state.settings.training && matches!(player.mode, PlayerMode::Human)
+}
+
+/// Grade the current player's decision with `grade`, if they're in training.
+/// The solver may have a new board to solve, so it grades a copy of the game on a
+/// blocking thread, without holding the game or its history.
+/// None if the game was replaced while grading, as the feedback is for the old one.
+async fn grade_in_training<F>(
+    state: &AppContext,
+    app: &tauri::AppHandle,
+    grade: F,
+) -> tauri::Result<Option<Feedback>>
+where
+    F: FnOnce(&GameState, &Mutex<SolverCache>) -> Option<Feedback> + Send + 'static,
+{
+    let (game_state, generation) = {
+        let game_state = state.game.lock().unwrap();
+        if !in_training(&game_state) {
+            return Ok(None);
+        }
+        (game_state.clone(), state.generation.load(Ordering::SeqCst))
     };
+    let app = app.clone();
+    let feedback = tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppContext>();
+        grade(&game_state, &state.solvers)
+    })
+    .await?;
+    Ok(feedback.filter(|_| state.generation.load(Ordering::SeqCst) == generation))
+}
+
+/// Risk the chosen columns (indices into `columns`) for the current player,
+/// with any `feedback` from grading the choice.
+fn risk(
+    first: ColumnID,
+    second: Option<ColumnID>,
+    feedback: Option<Feedback>,
+    state: &AppContext,
+    app: &tauri::AppHandle,
+) -> tauri::Result<GameState> {
+    println!("choosing columns: {:?} {:?}", first, second);
+    let mut game_state = state.game.lock().unwrap();
+    game_state.risk_columns(first, second)?;
+    game_state.feedback = feedback;
+    game_state.unlocked.clear();
     // println!("Risked columns: {:?}", game_state);
-    game_state.hops += 1;
     {
         // record outcome
-        let store = get_store(&app)?;
+        let store = get_store(app)?;
         game_state.write_to_store(&store)?;
         let mut history = state.hist.lock().unwrap();
         history.player_mut().record_choice(first, second);
@@ -149,22 +329,81 @@ pub fn choose_columns(
 #[tauri::command]
 /// Player has chosen to end their run, or has been forced to end it by
 /// pushing their luck too far, and running out of options.
-pub fn end_run(
+/// In training mode, banking is graded against the solver.
+pub async fn end_run(
     forced: bool,
-    state: tauri::State<AppContext>,
+    state: tauri::State<'_, AppContext>,
     app: tauri::AppHandle,
+) -> tauri::Result<GameState> {
+    let feedback = match forced {
+        true => None,
+        false => {
+            grade_in_training(&state, &app, |game_state, cache| {
+                game_state.grade_stop(cache)
+            })
+            .await?
+        }
+    };
+    finish_run(forced, feedback, &state, &app)
+}
+
+/// End the current player's run, banking their progress unless `forced`,
+/// with any `feedback` from grading the stop.
+fn finish_run(
+    forced: bool,
+    feedback: Option<Feedback>,
+    state: &AppContext,
+    app: &tauri::AppHandle,
 ) -> tauri::Result<GameState> {
     let mut game_state = state.game.lock().unwrap();
     let mut history = state.hist.lock().unwrap();
-    let store = get_store(&app)?;
+    let store = get_store(app)?;
     let outcome = forced.into();
+    let was_over = game_state.winner.is_some();
+    let player_index = game_state.current_player;
 
     game_state.next_player(outcome);
+    game_state.feedback = feedback;
     history.next_player(outcome, game_state.get_unavailable());
+    if !was_over {
+        forecast::extend_timeline(&game_state, &history, state, app, &store);
+    }
+    let player = &game_state.settings.players[player_index];
+    if let (PlayerMode::Human, Some(run)) = (player.mode, history.last_finished_run(player_index)) {
+        // learn how this human plays, for the bots
+        let mut models = OpponentModels::default();
+        if let Err(e) = models.update_from_store(&store) {
+            // already backed up, the bots start learning afresh
+            println!("{:#}", e);
+        }
+        let model = models.0.entry(player.key().to_string()).or_default();
+        model.learn(run);
+        game_state
+            .opponent_models
+            .insert(player_index, model.clone());
+        models.write_to_store(&store)?;
+    }
+    game_state.unlocked = achievements::unlock_earned(&game_state, &history, player_index, &store)?;
+    if let Some(winner) = game_state.winner.as_ref().filter(|_| !was_over) {
+        println!("Game Over! Player {} wins!", winner.name);
+        if game_state.is_human_vs_bot() {
+            // remember how the humans got on, for adaptive bots
+            let mut record = AdaptiveRecord::default();
+            if let Err(e) = record.update_from_store(&store) {
+                // already backed up, carry on from the default skill
+                println!("{:#}", e);
+            }
+            record.record_game(matches!(winner.mode, PlayerMode::Human));
+            record.write_to_store(&store)?;
+        }
+        ratings::rate_game(&game_state, &store)?;
+        career::archive_game(&game_state, &history, &store)?;
+    }
     println!("{}", history);
     println!("{:?}", game_state);
 
     game_state.write_to_store(&store)?;
     history.write_to_store(&store)?;
+    saves::keep_game(&game_state, &history, &store)?;
     Ok(game_state.clone())
 }
@@ -0,0 +1,57 @@
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{Achievement, AchievementInfo, Achievements, GameState, History, PlayerMode, Unlock},
+    utils::get_store,
+};
+
+/// Unlock the achievements a player earned with the run they've just finished,
+/// returning the ones they hadn't already unlocked. Only humans and profiled players
+/// unlock achievements.
+pub(super) fn unlock_earned<R: tauri::Runtime>(
+    game_state: &GameState,
+    history: &History,
+    player: usize,
+    store: &Store<R>,
+) -> anyhow::Result<Vec<Unlock>> {
+    let player_info = &game_state.settings.players[player];
+    if player_info.profile.is_none() && !matches!(player_info.mode, PlayerMode::Human) {
+        return Ok(vec![]);
+    }
+    let earned = game_state.achievements_earned(history, player);
+    if earned.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut achievements = Achievements::default();
+    if let Err(e) = achievements.update_from_store(store) {
+        // already backed up, carry on with a fresh set of unlocks
+        println!("{:#}", e);
+    }
+    let unlocked = achievements.unlock(player_info.key(), &earned);
+    achievements.write_to_store(store)?;
+    Ok(unlocked
+        .into_iter()
+        .map(|achievement| Unlock {
+            player,
+            info: achievement.into(),
+        })
+        .collect())
+}
+
+#[tauri::command]
+/// Return every achievement, with what it's for.
+pub fn get_achievements() -> Vec<AchievementInfo> {
+    Achievement::ALL
+        .into_iter()
+        .map(AchievementInfo::from)
+        .collect()
+}
+
+#[tauri::command]
+/// Return the achievements each player has unlocked, by profile id or name.
+pub fn get_unlocked_achievements(app: tauri::AppHandle) -> tauri::Result<Achievements> {
+    let store = get_store(&app)?;
+    let mut achievements = Achievements::default();
+    achievements.update_from_store(&store)?;
+    Ok(achievements)
+}
@@ -1,211 +1,301 @@
-use std::{cmp::Ordering, collections::HashSet};
+use anyhow::anyhow;
+use serde::Serialize;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+use tauri::{Emitter as _, Manager as _};
 
-use crate::state::{
-    calculate_croak_chance, AppContext, Choice, Column, ColumnID, DiceResult, PlayerMode,
+use super::{finish_run, risk, roll};
+use crate::{
+    state::{
+        AppContext, Choice, ChoiceRationale, DiceResult, GameState, Hint, HopRationale,
+        Personalities, Personality, PlayerID,
+    },
+    utils::get_store,
 };
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
-struct EvaluateColumn {
-    /// How many hops to the top
-    banked_distance: usize,
-    /// How many of those hops are not secure
-    risked: usize,
-    /// Has reached the top but not banked yet
-    topped: bool,
-    // /// Closest Opponent
-    // opponent_distance: usize,
-}
-
-impl EvaluateColumn {
-    fn evaluate(
-        columns: [Column; 11],
-        active_cols: &HashSet<ColumnID>,
-        player_index: usize,
-    ) -> HashSet<Self> {
-        active_cols
-            .iter()
-            .map(|col| {
-                let index = *col - 2; // convert from 2d6 to index
-                let Some(column) = columns.get(index) else {
-                    panic!("Invalid column index {}", col);
-                };
-                let banked_distance = column.height - column.hops[player_index];
-                Self {
-                    banked_distance,
-                    risked: column.risked,
-                    topped: banked_distance - column.risked <= 0,
-                }
-            })
-            .collect()
+/// Event name for each step of a bot's run played by the backend.
+const BOT_STEP: &str = "bot-step";
+/// Pause before the bot makes each decision.
+const DECISION_DELAY: Duration = Duration::from_millis(500);
+/// Pause after each decision, so the UI can show it before it happens.
+const ACTION_DELAY: Duration = Duration::from_millis(750);
+
+/// A step in a bot's run, streamed to the UI to animate.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum BotStep {
+    /// Bot has decided whether to hop or stop.
+    Decided { hop: bool, rationale: HopRationale },
+    /// Bot has rolled the dice.
+    Rolled { dice: DiceResult },
+    /// Bot has picked which of the dice options to take.
+    Chose {
+        choice: Choice,
+        rationale: ChoiceRationale,
+    },
+    /// Bot's chosen columns have been risked.
+    Moved { state: GameState },
+    /// Bot ran out of options and lost their progress this run.
+    Croaked { state: GameState },
+    /// Bot stopped and banked their progress.
+    Banked { state: GameState },
+}
+
+/// A bot's decision, along with the reasons for it if they were asked for.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Decision<T, R> {
+    Plain(T),
+    Explained { decision: T, rationale: R },
+}
+
+impl<T, R> Decision<T, R> {
+    fn new(decision: T, rationale: R, explain: Option<bool>) -> Self {
+        match explain {
+            Some(true) => Self::Explained {
+                decision,
+                rationale,
+            },
+            _ => Self::Plain(decision),
+        }
     }
 }
 
 #[tauri::command]
-/// Decide bot action, hop or stop
-pub fn check_continue(state: tauri::State<AppContext>) -> bool {
-    let game_state = state.game.lock().unwrap();
-    let player = &game_state.settings.players[game_state.current_player];
-    let name = &player.name;
-    println!("bot: {} is thinking...", name);
-    let active_cols = game_state.get_selected();
-    let inactive_cols = game_state.get_unavailable();
-    // croak chance 0.0 - 1.0
-    let evaluation =
-        EvaluateColumn::evaluate(game_state.columns, &active_cols, game_state.current_player);
-    if evaluation.iter().any(|col| col.topped) {
-        return false; // bank this progress
-    };
-    let hops = game_state.hops;
-    // Define how much the threshold decreases per hop made in the current turn
-    const RISK_AVERSION_PER_HOP: f64 = 0.05; // e.g., 5% more cautious per hop
-                                             // Define a minimum threshold to prevent it from becoming zero or negative too easily
-    const MINIMUM_RISK_THRESHOLD: f64 = 0.05; // e.g., always willing to take at least a 5% risk
-
-    let croak_chance = calculate_croak_chance(&active_cols, &inactive_cols);
-    let base_risk_threshold = match player.mode {
-        // Safe bot avoids risks. Stops if bust chance is relatively low.
-        PlayerMode::Safe => 0.35, // Base threshold for Safe bot
-        // Normal bot takes calculated risks. Standard threshold.
-        PlayerMode::Normal => 0.50, // Base threshold for Normal bot
-        // Risky bot pushes their luck. High tolerance for busting.
-        PlayerMode::Risky => 0.65, // Base threshold for Risky bot
-        PlayerMode::Human => panic!("Shouldn't be called for a human player"),
-    };
+/// Decide bot action, hop or stop, optionally explaining why.
+pub fn check_continue(
+    explain: Option<bool>,
+    state: tauri::State<AppContext>,
+) -> tauri::Result<Decision<bool, HopRationale>> {
+    let rationale = decide_hop(&state)?;
+    Ok(Decision::new(rationale.hop, rationale, explain))
+}
 
-    // Adjust threshold based on hops: subtract aversion factor for each hop made
-    let adjusted_risk_threshold =
-        (base_risk_threshold - (hops as f64 * RISK_AVERSION_PER_HOP)).max(MINIMUM_RISK_THRESHOLD);
+/// Name of the current player, if they're a bot that can decide for themselves.
+fn bot_name(game_state: &GameState) -> anyhow::Result<&str> {
+    let player = &game_state.settings.players[game_state.current_player];
+    match player.mode.personality() {
+        Some(_) => Ok(&player.name),
+        None => Err(anyhow!("{} is not a bot", player.name)),
+    }
+}
 
-    let should_continue = croak_chance < adjusted_risk_threshold;
+fn decide_hop(state: &AppContext) -> anyhow::Result<HopRationale> {
+    let game_state = state.game.lock().unwrap();
+    let name = bot_name(&game_state)?;
+    println!("bot: {} is thinking...", name);
+    let rationale = game_state.bot_explain_continue();
     println!(
-        "bot: {} has decided to {}",
+        "bot: {} has decided to {} ({:?})",
         name,
-        if should_continue { "hop" } else { "stop" }
+        if rationale.hop { "hop" } else { "stop" },
+        rationale
     );
-    should_continue
+    Ok(rationale)
 }
 
 #[tauri::command]
-/// Decide which column(s) to select
-pub fn choose_column(options: DiceResult, state: tauri::State<AppContext>) -> Choice {
+/// Decide which column(s) to select, optionally explaining why.
+pub fn choose_column(
+    options: DiceResult,
+    explain: Option<bool>,
+    state: tauri::State<AppContext>,
+) -> tauri::Result<Decision<Choice, ChoiceRationale>> {
+    let rationale = decide_choice(&options, &state)?;
+    Ok(Decision::new(rationale.choice, rationale, explain))
+}
+
+fn decide_choice(options: &DiceResult, state: &AppContext) -> anyhow::Result<ChoiceRationale> {
     let game_state = state.game.lock().unwrap();
-    let player_index = game_state.current_player;
-    let name = &game_state.settings.players[player_index].name;
+    let name = bot_name(&game_state)?;
+    if options.choices.is_empty() {
+        return Err(anyhow!("{} has no columns to choose from", name));
+    }
     println!("bot: {} is choosing a column...", name);
-    // Get indices of all other players
-    let opponent_indices: Vec<usize> = (0..game_state.settings.players.len())
-        .filter(|&idx| idx != player_index)
-        .collect();
-    let choices: HashSet<(usize, Option<usize>)> = options.choices;
-    let active_cols = &game_state.get_selected();
-    let columns = game_state.columns;
-    let mut attractiveness: Vec<(f64, Choice)> = choices
-        .iter()
-        .map(|&choice| {
-            // convert number to index, as the backend uses 0-based indexing and the user is
-            // choosing a 2d6 number, so we need to subtract 2 from the number.
-            let indexed_choice = (choice.0 - 2, choice.1.map(|x| x - 2));
-            let value = match indexed_choice {
-                (first, None) => {
-                    let Some(column) = columns.get(first) else {
-                        panic!("Invalid index {}", first);
-                    };
-                    column.rate(active_cols, player_index, &opponent_indices)
-                }
-                (first, Some(second)) => {
-                    // Sum the attractiveness of both columns for pairs
-                    let Some(column1) = columns.get(first) else {
-                        panic!("Invalid index {}", first);
-                    };
-                    let Some(column2) = columns.get(second) else {
-                        panic!("Invalid index {}", second);
-                    };
-                    let rating = column1.rate(active_cols, player_index, &opponent_indices)
-                        + column2.rate(active_cols, player_index, &opponent_indices);
-                    match first == second {
-                        // weight higher if the two values are the same.
-                        true => rating * 1.5,
-                        false => rating,
-                    }
-                }
-            };
-            (value, choice)
-        })
-        .collect();
-    // Sort descending: higher score is better
-    attractiveness.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
-
-    // Choose the best option
-    // .first() gives the highest score after descending sort.
-    // .unwrap() is safe because options.choices is guaranteed non-empty by game logic.
-    let choice = attractiveness.first().unwrap().1;
-    println!("bot: {} chose {:?}", name, choice);
-    choice
-}
-
-impl Column {
-    /// Evaluate the attractiveness of choosing this column on an arbitrary scale. Higher is better.
-    ///
-    /// # Arguments
-    /// * `active_cols` - Set of column IDs already selected in the current turn.
-    /// * `player_index` - Index of the current AI player.
-    /// * `opponent_indices` - Indices of all opponent players.
-    fn rate(
-        &self,
-        active_cols: &HashSet<ColumnID>,
-        player_index: usize,
-        opponent_indices: &[usize],
-    ) -> f64 {
-        let mut score = 0.0;
-
-        // --- Weights for different factors (these can be tuned) ---
-        const WEIGHT_ACTIVE: f64 = 5.0; // Bonus for using an already active column if < 3 are active
-        const WEIGHT_PROGRESS: f64 = 1.0; // Reward for progress already made
-        const WEIGHT_DISTANCE: f64 = 2.0; // Reward for being closer to the top
-        const WEIGHT_OPPONENT: f64 = 1.5; // Reward for blocking opponents who are close
-        const WEIGHT_PROBABILITY: f64 = 0.5; // Small reward for columns statistically easier to roll
-
-        // --- Calculations ---
-
-        // 1. Active Column Bonus: Prioritize using columns already started this turn
-        //    if we haven't picked 3 unique columns yet.
-        if active_cols.len() < 3 && active_cols.contains(&self.col) {
-            score += WEIGHT_ACTIVE;
+    let rationale = game_state.bot_explain_choice(&options.choices);
+    println!("bot: {} chose {:?}", name, rationale.choice);
+    Ok(rationale)
+}
+
+#[tauri::command]
+/// Advise the current human player what to do, ranking the offered `options` if they have rolled.
+pub fn get_hint(
+    options: Option<DiceResult>,
+    state: tauri::State<AppContext>,
+) -> tauri::Result<Hint> {
+    let game_state = state.game.lock().unwrap();
+    if !game_state.in_progress || game_state.winner.is_some() {
+        return Err(anyhow!("No game in progress").into());
+    }
+    let player = &game_state.settings.players[game_state.current_player];
+    if player.mode.personality().is_some() {
+        return Err(anyhow!("{} is a bot and doesn't need a hint", player.name).into());
+    }
+    Ok(game_state.hint(options.as_ref().map(|options| &options.choices)))
+}
+
+/// Clears the playing flag when the bot's run finishes, however it ends.
+struct Playing<'a>(&'a AtomicBool);
+
+impl Drop for Playing<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+#[tauri::command]
+/// Play the current bot's whole run in the background, emitting each step as a `bot-step` event.
+/// Does nothing if the run is already being played, so it is safe to call again.
+pub fn play_bot_turn(state: tauri::State<AppContext>, app: tauri::AppHandle) -> tauri::Result<()> {
+    let turn = {
+        let game_state = state.game.lock().unwrap();
+        if !game_state.in_progress || game_state.winner.is_some() {
+            return Err(anyhow!("No game in progress").into());
+        }
+        bot_name(&game_state)?;
+        BotTurn {
+            generation: state.generation.load(Ordering::SeqCst),
+            player: game_state.current_player,
+        }
+    };
+    if state.bot_playing.swap(true, Ordering::SeqCst) {
+        println!("bot: already playing this run");
+        return Ok(());
+    }
+    thread::spawn(move || {
+        let state = app.state::<AppContext>();
+        let playing = Playing(&state.bot_playing);
+        let finished = play_bot_run(turn, &state, &app);
+        // Ready for the next bot before the UI hears this run is over.
+        drop(playing);
+        let result = match finished {
+            Ok(Some(step)) => app.emit(BOT_STEP, step),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            println!("bot: failed to play run: {}", e);
         }
+    });
+    Ok(())
+}
+
+/// The bot's run being played, in the game it was started in.
+#[derive(Debug, Clone, Copy)]
+struct BotTurn {
+    /// `AppContext::generation` of the game.
+    generation: u64,
+    /// Index of the bot taking the run.
+    player: PlayerID,
+}
 
-        let current_hops = self.hops[player_index];
-        // Calculate state *after* the potential move (adding one hop).
-        let potential_hops = current_hops + 1;
-        let potential_distance = self.height.saturating_sub(potential_hops);
-
-        // 3. Progress Score: Reward columns where we've already invested hops.
-        score += WEIGHT_PROGRESS * (current_hops as f64);
-
-        // 4. Distance Score: Reward columns closer to the top. Higher score for smaller potential_distance.
-        //    Add 1.0 to denominator to avoid division by zero if topped (potential_distance is 0).
-        //    Scale by height to give slightly more weight to finishing taller columns.
-        score += WEIGHT_DISTANCE * (self.height as f64 / (potential_distance as f64 + 1.0));
-
-        // 5. Opponent Blocking Score: Consider blocking opponents close to winning this column.
-        let max_opponent_hops = opponent_indices
-            .iter()
-            .map(|&idx| self.hops[idx])
-            .max()
-            .unwrap_or(0); // Find the most advanced opponent on this column
-
-        if max_opponent_hops > 0 {
-            let opponent_distance = self.height.saturating_sub(max_opponent_hops);
-            // Add score, weighted more heavily if the opponent is closer (smaller opponent_distance).
-            score += WEIGHT_OPPONENT * (self.height as f64 / (opponent_distance as f64 + 1.0));
+impl BotTurn {
+    /// Whether the run is still the bot's to play, in the same game.
+    fn is_current(&self, state: &AppContext) -> bool {
+        let game_state = state.game.lock().unwrap();
+        state.generation.load(Ordering::SeqCst) == self.generation
+            && game_state.in_progress
+            && game_state.winner.is_none()
+            && game_state.current_player == self.player
+            && bot_name(&game_state).is_ok()
+    }
+}
+
+/// Play the bot's run until they bank or croak, pausing between steps.
+/// Returns the final step, or None if the game moved on without the bot, e.g. it was quit.
+fn play_bot_run(
+    turn: BotTurn,
+    state: &AppContext,
+    app: &tauri::AppHandle,
+) -> tauri::Result<Option<BotStep>> {
+    // Give up if the game has moved on without the bot.
+    let wait = |delay: Duration| {
+        thread::sleep(delay);
+        turn.is_current(state)
+    };
+    loop {
+        if !wait(DECISION_DELAY) {
+            return Ok(None);
         }
+        let rationale = decide_hop(state)?;
+        let hop = rationale.hop;
+        app.emit(BOT_STEP, BotStep::Decided { hop, rationale })?;
+        if !wait(ACTION_DELAY) {
+            return Ok(None);
+        }
+        if !hop {
+            let state = finish_run(false, None, state, app)?;
+            return Ok(Some(BotStep::Banked { state }));
+        }
+        let dice = roll(state, app)?;
+        app.emit(BOT_STEP, BotStep::Rolled { dice: dice.clone() })?;
+        if !wait(DECISION_DELAY) {
+            return Ok(None);
+        }
+        if dice.choices.is_empty() {
+            println!("bot: croaked!");
+            if !wait(ACTION_DELAY) {
+                return Ok(None);
+            }
+            let state = finish_run(true, None, state, app)?;
+            return Ok(Some(BotStep::Croaked { state }));
+        }
+        let rationale = decide_choice(&dice, state)?;
+        let choice = rationale.choice;
+        app.emit(BOT_STEP, BotStep::Chose { choice, rationale })?;
+        if !wait(ACTION_DELAY) {
+            return Ok(None);
+        }
+        // convert from 2d6 to index
+        let (first, second) = (choice.0 - 2, choice.1.map(|x| x - 2));
+        let state = risk(first, second, None, state, app)?;
+        app.emit(BOT_STEP, BotStep::Moved { state })?;
+    }
+}
 
-        // 6. Column Probability Score: Give a slight edge to columns corresponding to more probable dice rolls.
-        //    Maps column ID (0-10) to dice sum probability factor (1-6).
-        //    IDs 0/10 (sum 2/12) -> factor 1; ID 5 (sum 7) -> factor 6.
-        let probability_factor = 6.0 - (5.0 - self.col as f64).abs(); // Simple way to get 1..6..1 pattern
-        score += WEIGHT_PROBABILITY * probability_factor;
+#[tauri::command]
+/// Return all of the user's saved bot personalities, by name.
+pub fn get_personalities(app: tauri::AppHandle) -> tauri::Result<Personalities> {
+    let store = get_store(&app)?;
+    let mut personalities = Personalities::default();
+    personalities.update_from_store(&store)?;
+    Ok(personalities)
+}
 
-        // Ensure score is non-negative
-        score.max(0.0)
+#[tauri::command]
+/// Save a named bot personality, replacing any existing one with that name.
+pub fn save_personality(
+    name: String,
+    personality: Personality,
+    app: tauri::AppHandle,
+) -> tauri::Result<Personalities> {
+    println!("saving personality: {} {:?}", name, personality);
+    let store = get_store(&app)?;
+    let mut personalities = Personalities::default();
+    if let Err(e) = personalities.update_from_store(&store) {
+        // already backed up, carry on with none saved
+        println!("{:#}", e);
+    }
+    personalities.0.insert(name, personality);
+    personalities.write_to_store(&store)?;
+    Ok(personalities)
+}
+
+#[tauri::command]
+/// Remove a named bot personality.
+pub fn delete_personality(name: String, app: tauri::AppHandle) -> tauri::Result<Personalities> {
+    let store = get_store(&app)?;
+    let mut personalities = Personalities::default();
+    if let Err(e) = personalities.update_from_store(&store) {
+        // already backed up, carry on with none saved
+        println!("{:#}", e);
+    }
+    if personalities.0.remove(&name).is_none() {
+        return Err(anyhow!("No personality named {}", name).into());
     }
+    personalities.write_to_store(&store)?;
+    Ok(personalities)
 }
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{ArchivedGame, CareerStats, GameArchive, GameState, History},
+    utils::get_store,
+};
+
+/// Archive a game that has just been won, for the players' careers.
+pub(super) fn archive_game<R: tauri::Runtime>(
+    game_state: &GameState,
+    history: &History,
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    let summary = history.calculate_summary();
+    let Some(game) = ArchivedGame::new(game_state, history.clone(), summary) else {
+        return Ok(());
+    };
+    let mut archive = GameArchive::default();
+    if let Err(e) = archive.update_from_store(store) {
+        // already backed up, carry on with a fresh archive
+        println!("{:#}", e);
+    }
+    archive.record(game);
+    archive.write_to_store(store)
+}
+
+#[tauri::command]
+/// Return every player's statistics over all the games they've finished,
+/// by profile id, or by name for guests.
+pub fn get_career_stats(app: tauri::AppHandle) -> tauri::Result<BTreeMap<String, CareerStats>> {
+    let store = get_store(&app)?;
+    let mut archive = GameArchive::default();
+    archive.update_from_store(&store)?;
+    Ok(archive.careers())
+}
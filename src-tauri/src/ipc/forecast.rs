@@ -0,0 +1,57 @@
+use std::thread;
+
+use tauri::{Manager as _, Wry};
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{AppContext, GameState, History, DEFAULT_PLAYOUTS},
+    utils::get_store,
+};
+
+use super::saves;
+
+/// Estimate each player's chance of winning from where the game is now, for its timeline.
+/// The playouts take a while, so they're run off the game's thread, and each estimate
+/// waits for the one before so they're recorded in order. Each is tagged with the game's
+/// save slot and the run it follows, and is dropped if that game is no longer being played
+/// or the timeline has moved past it. An estimate that can't be made is recorded as empty,
+/// so the ones after it still line up with their runs.
+pub(super) fn extend_timeline(
+    game_state: &GameState,
+    history: &History,
+    state: &AppContext,
+    app: &tauri::AppHandle,
+    store: &Store<Wry>,
+) {
+    // a chance at the start, then one after each finished run
+    let run = history.runs_in_order().len().saturating_sub(1);
+    let slot = saves::current_slot(store);
+    let previous = state.forecast.lock().unwrap().take();
+    let (game_state, app) = (game_state.clone(), app.clone());
+    let forecast = thread::spawn(move || {
+        let chances = game_state.win_chances(DEFAULT_PLAYOUTS, rand::random());
+        if let Some(previous) = previous {
+            let _ = previous.join();
+        }
+        let state = app.state::<AppContext>();
+        let mut history = state.hist.lock().unwrap();
+        let saved = get_store(&app).and_then(|store| {
+            if saves::current_slot(&store) != slot || history.win_timeline.len() != run {
+                // a different game has been started or loaded since
+                return Ok(());
+            }
+            match chances {
+                Ok(chances) => history.record_win_chances(&chances),
+                Err(e) => {
+                    println!("Couldn't estimate the win chances: {:#}", e);
+                    history.skip_win_chances();
+                }
+            }
+            history.write_to_store(&store)
+        });
+        if let Err(e) = saved {
+            println!("Couldn't save the win chances: {:#}", e);
+        }
+    });
+    *state.forecast.lock().unwrap() = Some(forecast);
+}
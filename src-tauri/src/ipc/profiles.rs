@@ -0,0 +1,65 @@
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{Player, Profile, ProfileDetails, Profiles},
+    utils::get_store,
+};
+
+/// Load the player profiles, which have been backed up if they can't be.
+fn load_profiles<R: tauri::Runtime>(store: &Store<R>) -> anyhow::Result<Profiles> {
+    let mut profiles = Profiles::default();
+    profiles.update_from_store(store)?;
+    Ok(profiles)
+}
+
+/// Check the profiles players are playing as exist, and show them by their profile's name.
+pub(super) fn link_players<R: tauri::Runtime>(
+    players: &mut [Player],
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    if players.iter().all(|player| player.profile.is_none()) {
+        return Ok(());
+    }
+    load_profiles(store)?.link(players)
+}
+
+#[tauri::command]
+/// Return every player profile, by id.
+pub fn get_profiles(app: tauri::AppHandle) -> tauri::Result<Profiles> {
+    let store = get_store(&app)?;
+    Ok(load_profiles(&store)?)
+}
+
+#[tauri::command]
+/// Create a player profile, with a new id.
+pub fn create_profile(details: ProfileDetails, app: tauri::AppHandle) -> tauri::Result<Profile> {
+    let store = get_store(&app)?;
+    let mut profiles = load_profiles(&store)?;
+    let profile = profiles.create(details)?;
+    profiles.write_to_store(&store)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+/// Change a player profile's details, keeping its id.
+pub fn update_profile(
+    id: String,
+    details: ProfileDetails,
+    app: tauri::AppHandle,
+) -> tauri::Result<Profile> {
+    let store = get_store(&app)?;
+    let mut profiles = load_profiles(&store)?;
+    let profile = profiles.update(&id, details)?;
+    profiles.write_to_store(&store)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+/// Delete a player profile.
+pub fn delete_profile(id: String, app: tauri::AppHandle) -> tauri::Result<Profiles> {
+    let store = get_store(&app)?;
+    let mut profiles = load_profiles(&store)?;
+    profiles.delete(&id)?;
+    profiles.write_to_store(&store)?;
+    Ok(profiles)
+}
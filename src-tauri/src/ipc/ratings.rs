@@ -0,0 +1,45 @@
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{BotMatch, GameState, Personalities, Ratings, Standing},
+    utils::get_store,
+};
+
+/// Load the ratings, which have been backed up if they can't be.
+fn load_ratings<R: tauri::Runtime>(store: &Store<R>) -> anyhow::Result<Ratings> {
+    let mut ratings = Ratings::default();
+    ratings.update_from_store(store)?;
+    Ok(ratings)
+}
+
+/// Update the players' ratings after a game has been won.
+pub(super) fn rate_game<R: tauri::Runtime>(
+    game_state: &GameState,
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    let mut ratings = Ratings::default();
+    if let Err(e) = ratings.update_from_store(store) {
+        // already backed up, carry on with fresh ratings
+        println!("{:#}", e);
+    }
+    let mut personalities = Personalities::default();
+    if let Err(e) = personalities.update_from_store(store) {
+        println!("{:#}", e);
+    }
+    ratings.record_game(game_state, &personalities);
+    ratings.write_to_store(store)
+}
+
+#[tauri::command]
+/// Return the standings of every rated player and bot, highest rated first.
+pub fn get_leaderboard(app: tauri::AppHandle) -> tauri::Result<Vec<Standing>> {
+    let store = get_store(&app)?;
+    Ok(load_ratings(&store)?.leaderboard())
+}
+
+#[tauri::command]
+/// Return the bots to play against the player with this profile id or name, fairest first.
+pub fn recommend_bots(player: String, app: tauri::AppHandle) -> tauri::Result<Vec<BotMatch>> {
+    let store = get_store(&app)?;
+    Ok(load_ratings(&store)?.recommend_bots(&player))
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use tauri::Manager as _;
+
+use crate::{
+    state::{AppContext, GameRecord, SaveSlots, SaveSummary, SavedGame},
+    utils::get_store,
+};
+
+use super::saves::read_game;
+
+/// Where a game record file goes. Relative paths are from the user's documents folder.
+fn record_path(path: &str, app: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    Ok(app.path().document_dir()?.join(path))
+}
+
+#[tauri::command]
+/// Write a saved game to a game record file, returning where it was written.
+pub fn export_game_record(
+    id: String,
+    path: String,
+    state: tauri::State<AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<String> {
+    let store = get_store(&app)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    let game = read_game(&slots, &id, &state, &store)?;
+    let name = slots.get(&id)?.name.clone();
+    let record = GameRecord::new(name, &game.state, &game.history, None);
+    let path = record_path(&path, &app)?;
+    let file = serde_json::to_string_pretty(&record)?;
+    std::fs::write(&path, file)
+        .with_context(|| format!("couldn't write the game record to {}", path.display()))?;
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+/// Read a game record file into a new save slot, once it has been played back to check it
+/// follows the rules.
+pub fn import_game_record(path: String, app: tauri::AppHandle) -> tauri::Result<Vec<SaveSummary>> {
+    let path = record_path(&path, &app)?;
+    let file = std::fs::read_to_string(&path)
+        .with_context(|| format!("couldn't read {}", path.display()))?;
+    let record: GameRecord = serde_json::from_str(&file).context("not a Can't Hop game record")?;
+    let (state, history) = record
+        .replay()
+        .context("the game record isn't a valid game")?;
+    let store = get_store(&app)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    let id = slots.import(&state, &history, record.name);
+    SavedGame { state, history }.write_to_store(&store, &id)?;
+    slots.write_to_store(&store)?;
+    Ok(slots.list())
+}
+
+#[tauri::command]
+/// Return a saved game written in Can't Hop notation.
+pub fn get_game_notation(
+    id: String,
+    state: tauri::State<AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<String> {
+    let store = get_store(&app)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    let game = read_game(&slots, &id, &state, &store)?;
+    let name = slots.get(&id)?.name.clone();
+    let record = GameRecord::new(name, &game.state, &game.history, None);
+    Ok(record.to_notation())
+}
+
+#[tauri::command]
+/// Read a game written in Can't Hop notation into a new save slot,
+/// once it has been played back to check it follows the rules.
+pub fn import_game_notation(
+    notation: String,
+    app: tauri::AppHandle,
+) -> tauri::Result<Vec<SaveSummary>> {
+    let record = GameRecord::from_notation(&notation)?;
+    let (state, history) = record.replay()?;
+    let store = get_store(&app)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    let id = slots.import(&state, &history, record.name);
+    SavedGame { state, history }.write_to_store(&store, &id)?;
+    slots.write_to_store(&store)?;
+    Ok(slots.list())
+}
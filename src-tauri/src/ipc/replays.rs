@@ -0,0 +1,56 @@
+use crate::{
+    state::{AppContext, GameState, Replay, ReplayFrame, ReplayPosition, SaveSlots, SavedGame},
+    utils::get_store,
+};
+
+use super::saves::{leave_game, read_game};
+
+/// Replay a saved game from its start, at the given position.
+fn replay_at(game: &SavedGame, position: ReplayPosition) -> anyhow::Result<Replay> {
+    let mut replay = Replay::new(game.state.settings.clone(), &game.history)?;
+    replay.seek(position)?;
+    Ok(replay)
+}
+
+#[tauri::command]
+/// Show a saved game as it was after some rolls of one of its runs.
+pub fn get_replay_frame(
+    id: String,
+    position: ReplayPosition,
+    state: tauri::State<AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<ReplayFrame> {
+    let store = get_store(&app)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    let game = read_game(&slots, &id, &state, &store)?;
+    Ok(replay_at(&game, position)?.frame()?)
+}
+
+#[tauri::command]
+/// Carry on a saved game from a point in its replay, in a new save slot, to practise it.
+/// The saved game is left as it is, and so is the one being played.
+pub fn practise_from_position(
+    id: String,
+    position: ReplayPosition,
+    state: tauri::State<AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<GameState> {
+    let mut game_state = state.game.lock().unwrap();
+    let mut history = state.hist.lock().unwrap();
+    super::replace_game(&state);
+    let store = get_store(&app)?;
+    leave_game(&game_state, &history, &store)?;
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(&store)?;
+    // the game being played has just been put away, so it's read like any other
+    let game = SavedGame::read_from_store(&store, &id)?;
+    let (practice, practice_history) = replay_at(&game, position)?.reconstruct()?;
+    let name = format!("{} from run {}", slots.get(&id)?.name, position.run + 1);
+    slots.start(&practice, &practice_history, Some(name));
+    (*game_state, *history) = (practice, practice_history);
+    slots.write_to_store(&store)?;
+    game_state.write_to_store(&store)?;
+    history.write_to_store(&store)?;
+    Ok(game_state.clone())
+}
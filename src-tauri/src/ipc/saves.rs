@@ -0,0 +1,150 @@
+use tauri_plugin_store::Store;
+
+use crate::{
+    state::{AppContext, GameState, History, SaveSlots, SaveSummary, SavedGame},
+    utils::get_store,
+};
+
+/// Update the save slot of the game being played, if there is a game being played.
+/// Only its summary is updated, as the game is kept as the game state and history.
+pub(super) fn keep_game<R: tauri::Runtime>(
+    game_state: &GameState,
+    history: &History,
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    if !game_state.in_progress {
+        return Ok(());
+    }
+    let mut slots = SaveSlots::default();
+    if let Err(e) = slots.update_from_store(store) {
+        // already backed up, carry on with this game in a fresh set of slots
+        println!("{:#}", e);
+    }
+    slots.keep(game_state, history);
+    slots.write_to_store(store)
+}
+
+/// Stop playing the game being played, putting it away under its save slot's own key
+/// so it's left as it is when another takes its place.
+pub(super) fn leave_game<R: tauri::Runtime>(
+    game_state: &GameState,
+    history: &History,
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    let mut slots = SaveSlots::default();
+    if let Err(e) = slots.update_from_store(store) {
+        println!("{:#}", e);
+    }
+    if game_state.in_progress {
+        let id = slots.keep(game_state, history);
+        let game = SavedGame {
+            state: game_state.clone(),
+            history: history.clone(),
+        };
+        game.write_to_store(store, &id)?;
+    }
+    slots.current = None;
+    slots.write_to_store(store)
+}
+
+/// Give a new game its own save slot, so it doesn't overwrite the last one played.
+pub(super) fn start_slot<R: tauri::Runtime>(
+    game_state: &GameState,
+    history: &History,
+    name: Option<String>,
+    store: &Store<R>,
+) -> anyhow::Result<()> {
+    let mut slots = SaveSlots::default();
+    if let Err(e) = slots.update_from_store(store) {
+        println!("{:#}", e);
+    }
+    slots.start(game_state, history, name);
+    slots.write_to_store(store)
+}
+
+/// A saved game, which is the one being played if it's the current one.
+/// Takes the game and its history, so mustn't be called while holding them.
+pub(super) fn read_game<R: tauri::Runtime>(
+    slots: &SaveSlots,
+    id: &str,
+    state: &AppContext,
+    store: &Store<R>,
+) -> anyhow::Result<SavedGame> {
+    if slots.current.as_deref() != Some(id) {
+        return SavedGame::read_from_store(store, id);
+    }
+    let game_state = state.game.lock().unwrap();
+    let history = state.hist.lock().unwrap();
+    Ok(SavedGame {
+        state: game_state.clone(),
+        history: history.clone(),
+    })
+}
+
+/// Id of the saved game being played, if there is one.
+pub(super) fn current_slot<R: tauri::Runtime>(store: &Store<R>) -> Option<String> {
+    load_slots(store).ok()?.current
+}
+
+/// Load the saved games, which have been backed up if they can't be.
+fn load_slots<R: tauri::Runtime>(store: &Store<R>) -> anyhow::Result<SaveSlots> {
+    let mut slots = SaveSlots::default();
+    slots.update_from_store(store)?;
+    Ok(slots)
+}
+
+#[tauri::command]
+/// Return a summary of every saved game, most recently played first.
+pub fn list_saves(app: tauri::AppHandle) -> tauri::Result<Vec<SaveSummary>> {
+    let store = get_store(&app)?;
+    Ok(load_slots(&store)?.list())
+}
+
+#[tauri::command]
+/// Carry on playing a saved game, keeping the one being played in its own slot.
+pub fn load_save(
+    id: String,
+    state: tauri::State<AppContext>,
+    app: tauri::AppHandle,
+) -> tauri::Result<GameState> {
+    let mut game_state = state.game.lock().unwrap();
+    let mut history = state.hist.lock().unwrap();
+    super::replace_game(&state);
+    let store = get_store(&app)?;
+    leave_game(&game_state, &history, &store)?;
+    let mut slots = load_slots(&store)?;
+    let game = SavedGame::read_from_store(&store, &id)?;
+    slots.load(&id)?;
+    (*game_state, *history) = (game.state, game.history);
+    slots.write_to_store(&store)?;
+    game_state.write_to_store(&store)?;
+    history.write_to_store(&store)?;
+    // it's kept as the game state and history while it's played
+    SavedGame::remove_from_store(&store, &id);
+    Ok(game_state.clone())
+}
+
+#[tauri::command]
+/// Give a saved game a new name.
+pub fn rename_save(
+    id: String,
+    name: String,
+    app: tauri::AppHandle,
+) -> tauri::Result<Vec<SaveSummary>> {
+    let store = get_store(&app)?;
+    let mut slots = load_slots(&store)?;
+    slots.rename(&id, name)?;
+    slots.write_to_store(&store)?;
+    Ok(slots.list())
+}
+
+#[tauri::command]
+/// Delete a saved game, which can't be the one being played.
+pub fn delete_save(id: String, app: tauri::AppHandle) -> tauri::Result<Vec<SaveSummary>> {
+    let store = get_store(&app)?;
+    let mut slots = load_slots(&store)?;
+    slots.delete(&id)?;
+    slots.write_to_store(&store)?;
+    SavedGame::remove_from_store(&store, &id);
+    Ok(slots.list())
+}
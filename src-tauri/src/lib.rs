@@ -1,7 +1,7 @@
 use tauri::Manager;
 
 mod ipc;
-mod state;
+pub mod state;
 mod utils;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -25,8 +25,38 @@ pub fn run() {
             ipc::get_game_state,
             ipc::get_name,
             ipc::get_game_statistics,
+            ipc::get_game_review,
+            ipc::get_opponent_models,
+            ipc::get_roll_analysis,
+            ipc::get_run_outlook,
+            ipc::get_optimal_play,
+            ipc::get_win_chances,
+            ipc::achievements::get_achievements,
+            ipc::achievements::get_unlocked_achievements,
             ipc::ai::check_continue,
             ipc::ai::choose_column,
+            ipc::ai::play_bot_turn,
+            ipc::ai::get_hint,
+            ipc::ai::get_personalities,
+            ipc::ai::save_personality,
+            ipc::ai::delete_personality,
+            ipc::career::get_career_stats,
+            ipc::profiles::get_profiles,
+            ipc::profiles::create_profile,
+            ipc::profiles::update_profile,
+            ipc::profiles::delete_profile,
+            ipc::ratings::get_leaderboard,
+            ipc::ratings::recommend_bots,
+            ipc::records::export_game_record,
+            ipc::records::import_game_record,
+            ipc::records::get_game_notation,
+            ipc::records::import_game_notation,
+            ipc::replays::get_replay_frame,
+            ipc::replays::practise_from_position,
+            ipc::saves::list_saves,
+            ipc::saves::load_save,
+            ipc::saves::rename_save,
+            ipc::saves::delete_save,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,17 +1,70 @@
+mod achievement;
+mod adaptive;
+mod analysis;
+mod bot;
+mod career;
 mod columns;
+mod forecast;
 mod game;
+mod hint;
 mod logic;
+mod notation;
+mod opponent;
+mod outlook;
+mod personality;
 mod player;
+mod profile;
+mod rating;
+mod record;
+mod replay;
+mod review;
+mod save;
+mod saves;
+mod simulation;
+mod solver;
 mod stats;
+mod training;
+mod variant;
 
+pub use achievement::{Achievement, AchievementInfo, Achievements, Unlock};
+pub use adaptive::AdaptiveRecord;
+pub use analysis::{analyse_roll, find_bust_rolls, BustRoll, RollAnalysis};
+pub use bot::{ChoiceRationale, ChoiceScore, ColumnScore, HopRationale};
+pub use career::{ArchivedGame, CareerGame, CareerStats, GameArchive, WinRecord};
 pub use columns::Column;
+pub use forecast::{WinChances, DEFAULT_PLAYOUTS, MAX_PLAYOUTS};
 pub use game::{GameState, GameStateMutex};
-pub use logic::{calculate_croak_chance, evaluate_moves};
-use player::Player;
-pub use player::PlayerMode;
+pub use hint::{ChoiceHint, Hint};
+pub use logic::{
+    calculate_croak_chance, calculate_roll_odds, evaluate_moves, preview_choices, throw_dice,
+    ChoicePreview, RollOdds,
+};
+pub use opponent::{OpponentModel, OpponentModels};
+pub use outlook::{RunOutlook, MAX_OUTLOOK_ROLLS};
+pub use personality::{Personalities, Personality};
+pub use player::{Player, PlayerMode, RunOutcome};
+pub use profile::{Profile, ProfileDetails, ProfileID, Profiles};
+pub use rating::{BotMatch, Rating, RatingChange, Ratings, Standing, INITIAL_RATING};
+pub use record::{GameRecord, RecordedRun, RecordedTurn, Ruleset, RECORD_FORMAT, RECORD_VERSION};
+pub use replay::{Replay, ReplayFrame, ReplayPosition};
+pub use review::{Action, Blunder, PlayerReview};
+pub use save::SAVE_VERSION;
+pub use saves::{SaveSlots, SaveSummary, SavedGame};
 use serde::{Deserialize, Serialize};
-pub use stats::{HistoryMutex, StatsSummary};
-use std::{collections::HashSet, fmt::Debug};
+pub use simulation::{Estimate, MatchupReport, Simulation, SimulationResult, Strategy};
+pub use solver::{Objective, OptimalPlay, SolverCache};
+pub use stats::{History, HistoryMutex, StatsSummary};
+use std::{
+    collections::HashSet,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, AtomicU64},
+        Mutex,
+    },
+    thread::JoinHandle,
+};
+pub use training::{Feedback, Grade};
+pub use variant::{SolvedVariant, Variant};
 
 pub type Choice = (ColumnID, Option<ColumnID>);
 pub type PlayerID = usize;
@@ -21,6 +74,16 @@ pub type ColumnID = usize;
 pub struct AppContext {
     pub game: GameStateMutex,
     pub hist: HistoryMutex,
+    /// Set while the backend is playing a bot's run.
+    pub bot_playing: AtomicBool,
+    /// Bumped whenever the game is swapped for another, while holding `game`,
+    /// so work started for the old one can tell it has gone.
+    pub generation: AtomicU64,
+    /// Solved runs for recent boards, as solving one takes a while.
+    pub solvers: Mutex<SolverCache>,
+    /// The latest estimate of the win chances for the timeline. Each one waits for the one
+    /// before it, so they're recorded in the order of the runs.
+    pub forecast: Mutex<Option<JoinHandle<()>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,10 +92,49 @@ pub struct SettingsState {
     pub players: Vec<Player>,
     /// Number of columns required to win
     win_cols: usize,
+    /// Grade each human's decisions against the solver as they play.
+    #[serde(default)]
+    pub training: bool,
+}
+
+impl SettingsState {
+    /// Settings for a game between the given players.
+    pub fn new(players: Vec<Player>, win_cols: usize) -> Self {
+        Self {
+            players,
+            win_cols,
+            training: false,
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct DiceResult {
     pub dice: [usize; 4],
     pub choices: HashSet<Choice>,
+    /// Odds of the next roll after taking each choice, for the player to compare them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previews: Vec<ChoicePreview>,
+}
+
+/// A new game between `players` humans, for tests.
+#[cfg(test)]
+pub(crate) fn human_game(players: usize, win_cols: usize) -> GameState {
+    let players = (0..players)
+        .map(|id| Player::new(id, PlayerMode::Human, format!("Player {}", id + 1)))
+        .collect();
+    let mut game = GameState::default();
+    game.new_game(SettingsState::new(players, win_cols));
+    game
+}
+
+/// A new game between two humans where only 2, 6, 7, 8 & 12 are left,
+/// to keep the board quick to solve, for tests.
+#[cfg(test)]
+pub(crate) fn small_board() -> GameState {
+    let mut game = human_game(2, 3);
+    for idx in [1, 2, 3, 7, 8, 9] {
+        game.columns[idx].locked = Some(1);
+    }
+    game
 }
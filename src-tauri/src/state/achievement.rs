@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::Store;
+
+use super::{
+    logic::calculate_croak_chance, player::RunOutcome, save, saves::now, stats::History, GameState,
+    PlayerID,
+};
+
+/// Chance of croaking (0.0 - 1.0) a roll has to have had, to bank with nerves of steel.
+const NERVE_CROAK_CHANCE: f64 = 0.5;
+
+/// Something to aim for over and above winning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Win with both of the shortest columns, 2 and 12.
+    BothEnds,
+    /// Bank a run after surviving a roll that was more likely than not to croak.
+    NervesOfSteel,
+    /// Claim a column from the bottom in a single run.
+    InOneGo,
+    /// Win without croaking once.
+    Flawless,
+}
+
+impl Achievement {
+    /// Every achievement, in the order they're shown.
+    pub const ALL: [Achievement; 4] = [
+        Achievement::BothEnds,
+        Achievement::NervesOfSteel,
+        Achievement::InOneGo,
+        Achievement::Flawless,
+    ];
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::BothEnds => "Both Ends",
+            Achievement::NervesOfSteel => "Nerves of Steel",
+            Achievement::InOneGo => "In One Go",
+            Achievement::Flawless => "Flawless",
+        }
+    }
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::BothEnds => "Win a game having claimed both 2 and 12.",
+            Achievement::NervesOfSteel => {
+                "Bank a run after surviving a roll with a 50% or higher chance to croak."
+            }
+            Achievement::InOneGo => "Claim a column from the bottom in a single run.",
+            Achievement::Flawless => "Win a game without croaking.",
+        }
+    }
+}
+
+/// An achievement with what it's for, to show the players.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AchievementInfo {
+    pub achievement: Achievement,
+    pub title: String,
+    pub description: String,
+}
+
+impl From<Achievement> for AchievementInfo {
+    fn from(achievement: Achievement) -> Self {
+        Self {
+            achievement,
+            title: achievement.title().to_string(),
+            description: achievement.description().to_string(),
+        }
+    }
+}
+
+/// An achievement a player has just unlocked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unlock {
+    /// Index of the player who unlocked it.
+    pub player: PlayerID,
+    #[serde(flatten)]
+    pub info: AchievementInfo,
+}
+
+/// Achievements each player has unlocked, by profile id or name, with when they unlocked them
+/// in seconds since the Unix epoch.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Achievements(pub BTreeMap<String, BTreeMap<Achievement, u64>>);
+
+impl GameState {
+    /// Achievements a player has earned with the run they've just finished.
+    pub fn achievements_earned(&self, history: &History, player: PlayerID) -> Vec<Achievement> {
+        let Some(run) = history.last_finished_run(player) else {
+            return vec![];
+        };
+        let won = self
+            .winner
+            .as_ref()
+            .is_some_and(|winner| winner.id == self.settings.players[player].id);
+        let won_cols = &self.settings.players[player].won_cols;
+        Achievement::ALL
+            .into_iter()
+            .filter(|achievement| match achievement {
+                Achievement::BothEnds => won && won_cols.contains(&2) && won_cols.contains(&12),
+                Achievement::NervesOfSteel => {
+                    run.outcome == RunOutcome::Banked
+                        && run.turns.iter().any(|turn| {
+                            calculate_croak_chance(&turn.active_cols, &run.inactive_cols)
+                                >= NERVE_CROAK_CHANCE
+                        })
+                }
+                Achievement::InOneGo => {
+                    let mut hops = [0; 11];
+                    for (first, second) in run.turns.iter().filter_map(|turn| turn.chosen) {
+                        hops[first] += 1;
+                        if let Some(second) = second {
+                            hops[second] += 1;
+                        }
+                    }
+                    run.outcome == RunOutcome::Banked
+                        && self.columns.iter().zip(hops).any(|(column, hops)| {
+                            column.locked == Some(player) && hops >= column.height
+                        })
+                }
+                Achievement::Flawless => {
+                    won && history.players[player]
+                        .runs()
+                        .iter()
+                        .all(|run| run.outcome != RunOutcome::Croaked)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Achievements {
+    /// Unlock achievements for a player, returning the ones they hadn't already unlocked.
+    pub fn unlock(&mut self, key: &str, earned: &[Achievement]) -> Vec<Achievement> {
+        let unlocked = self.0.entry(key.to_string()).or_default();
+        let time = now();
+        earned
+            .iter()
+            .copied()
+            .filter(|achievement| unlocked.insert(*achievement, time).is_none())
+            .collect()
+    }
+    /// Update unlocked achievements from disk. If they can't be loaded, they're backed up
+    /// and nobody has unlocked any instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "achievements") {
+            Ok(achievements) => *self = achievements.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save unlocked achievements to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "achievements", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::DiceResult;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_achievements_earned() {
+        let mut state = GameState::default();
+        state.new_game(GameState::default().settings);
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        // Player 1 climbs all of column 2 in one run, with 2, 3 & 12 risked on the last roll.
+        for (chosen, active) in [((0, Some(0)), vec![]), ((0, None), vec![2, 3, 12])] {
+            let active: HashSet<_> = active.into_iter().collect();
+            let player = history.player_mut();
+            player.record_roll(&DiceResult::default(), &active);
+            player.record_choice(chosen.0, chosen.1);
+        }
+        history.next_player(RunOutcome::Banked, HashSet::new());
+        state.columns[0].hops[0] = 3;
+        state.columns[0].locked = Some(0);
+        state.settings.players[0].won_cols = vec![2, 7, 12];
+        state.winner = Some(state.settings.players[0].clone());
+
+        assert_eq!(state.achievements_earned(&history, 0), Achievement::ALL);
+        assert!(state.achievements_earned(&history, 1).is_empty());
+
+        let mut achievements = Achievements::default();
+        let earned = [Achievement::InOneGo, Achievement::Flawless];
+        assert_eq!(achievements.unlock("grandma", &earned), earned);
+        assert!(achievements.unlock("grandma", &earned).is_empty());
+        assert_eq!(achievements.unlock("grandpa", &earned[..1]).len(), 1);
+    }
+}
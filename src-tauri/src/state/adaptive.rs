@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tauri_plugin_store::Store;
+
+use super::{personality::Personality, player::PlayerMode, save, GameState, PlayerID};
+
+/// Number of recent games remembered when judging the humans' skill.
+const RECENT_GAMES: usize = 10;
+/// Skill assumed before any games have been played, and for games without humans.
+pub const DEFAULT_SKILL: f64 = 0.5;
+
+/// Recent results of humans playing against bots, used to tune adaptive bots.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AdaptiveRecord {
+    /// Most recent games last, true if a human won.
+    results: VecDeque<bool>,
+}
+
+impl AdaptiveRecord {
+    /// Remember the result of a finished game, forgetting the oldest if needed.
+    pub fn record_game(&mut self, human_won: bool) {
+        self.results.push_back(human_won);
+        while self.results.len() > RECENT_GAMES {
+            self.results.pop_front();
+        }
+    }
+    /// Humans' recent win rate (0.0 - 1.0), pulled towards the default while there are few games.
+    pub fn skill(&self) -> f64 {
+        let wins = self.results.iter().filter(|&&won| won).count() as f64;
+        (wins + 2.0 * DEFAULT_SKILL) / (self.results.len() as f64 + 2.0)
+    }
+    /// Update the record from disk. If it can't be loaded, it's backed up
+    /// and the humans start from the default skill instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "adaptive") {
+            Ok(record) => *self = record.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save the record to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "adaptive", self)
+    }
+}
+
+impl Personality {
+    /// Personality for an adaptive bot playing at the given strength (0.0 - 1.0).
+    /// Weak bots play like the Safe bot, strong ones like the Risky bot.
+    pub fn adaptive(strength: f64) -> Self {
+        let safe = Personality::SAFE.risk_threshold;
+        let risky = Personality::RISKY.risk_threshold;
+        Self {
+            risk_threshold: safe + (risky - safe) * strength,
+            ..Personality::NORMAL
+        }
+    }
+}
+
+impl GameState {
+    /// Whether the game has both human and bot players.
+    pub fn is_human_vs_bot(&self) -> bool {
+        let players = &self.settings.players;
+        let is_human = |mode: &PlayerMode| matches!(mode, PlayerMode::Human);
+        players.iter().any(|p| is_human(&p.mode)) && players.iter().any(|p| !is_human(&p.mode))
+    }
+    /// How far through the game a player is, in columns won.
+    /// Partial progress on columns still open counts towards it.
+    fn progress(&self, player: PlayerID) -> f64 {
+        self.columns
+            .iter()
+            .filter(|col| col.locked.is_none() || col.locked == Some(player))
+            .map(|col| (col.hops[player] as f64 / col.height as f64).min(1.0))
+            .sum()
+    }
+    /// Strength (0.0 - 1.0) the current adaptive bot should play at.
+    /// Starts from the humans' recent skill, then plays harder when behind
+    /// the leading human and eases off when ahead, to keep the game close.
+    pub fn adaptive_strength(&self) -> f64 {
+        let player = self.current_player;
+        let players = &self.settings.players;
+        let humans: Vec<PlayerID> = (0..players.len())
+            .filter(|&idx| matches!(players[idx].mode, PlayerMode::Human))
+            .collect();
+        // Without any humans, keep up with the leading opponent instead.
+        let rivals = if humans.is_empty() {
+            (0..players.len()).filter(|&idx| idx != player).collect()
+        } else {
+            humans
+        };
+        let leader = rivals
+            .iter()
+            .map(|&idx| self.progress(idx))
+            .fold(0.0, f64::max);
+        let gap = (leader - self.progress(player)) / self.settings.win_cols.max(1) as f64;
+        (self.adaptive_skill + gap).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_skill_starts_at_default() {
+        assert_eq!(AdaptiveRecord::default().skill(), DEFAULT_SKILL);
+    }
+
+    #[test]
+    fn test_skill_only_remembers_recent_games() {
+        let mut record = AdaptiveRecord::default();
+        for _ in 0..RECENT_GAMES {
+            record.record_game(false);
+        }
+        let losing = record.skill();
+        for _ in 0..RECENT_GAMES {
+            record.record_game(true);
+        }
+        assert!(losing < DEFAULT_SKILL);
+        assert_eq!(record.results.len(), RECENT_GAMES);
+        assert_eq!(record.skill(), 1.0 - losing);
+    }
+}
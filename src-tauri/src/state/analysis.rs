@@ -0,0 +1,97 @@
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+};
+
+use super::{
+    logic::{all_rolls, calculate_roll_odds, pair_sums, playable_columns, RollOdds},
+    ColumnID, GameState,
+};
+
+/// Dice that would leave the player with no move, in any order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BustRoll {
+    /// The dice, lowest first.
+    pub dice: [usize; 4],
+    /// How many of the 1296 ordered rolls show these dice.
+    pub ways: usize,
+}
+
+/// Breakdown of the next roll, for learning why some columns are safer than others.
+#[derive(Debug, Clone, Serialize)]
+pub struct RollAnalysis {
+    #[serde(flatten)]
+    pub odds: RollOdds,
+    /// Every roll that would croak, most likely first.
+    pub bust_rolls: Vec<BustRoll>,
+}
+
+/// List the dice that would leave no move with the given columns active, most likely first.
+pub fn find_bust_rolls(
+    active_cols: &HashSet<ColumnID>,
+    inactive_cols: &HashSet<ColumnID>,
+) -> Vec<BustRoll> {
+    let playable = playable_columns(active_cols, inactive_cols);
+    let mut busts: BTreeMap<[usize; 4], usize> = BTreeMap::new();
+    for dice in all_rolls() {
+        if !pair_sums(dice).iter().any(|&sum| playable[sum]) {
+            let mut dice = dice;
+            dice.sort_unstable();
+            *busts.entry(dice).or_default() += 1;
+        }
+    }
+    let mut bust_rolls: Vec<BustRoll> = busts
+        .into_iter()
+        .map(|(dice, ways)| BustRoll { dice, ways })
+        .collect();
+    // Stable, so equally likely rolls stay in dice order.
+    bust_rolls.sort_by_key(|roll| Reverse(roll.ways));
+    bust_rolls
+}
+
+/// Analyse the next roll with the given columns active.
+pub fn analyse_roll(
+    active_cols: &HashSet<ColumnID>,
+    inactive_cols: &HashSet<ColumnID>,
+) -> RollAnalysis {
+    RollAnalysis {
+        odds: calculate_roll_odds(active_cols, inactive_cols),
+        bust_rolls: find_bust_rolls(active_cols, inactive_cols),
+    }
+}
+
+impl GameState {
+    /// Analyse the current player's next roll.
+    pub fn analyse_roll(&self) -> RollAnalysis {
+        analyse_roll(&self.get_selected(), &self.get_unavailable())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::calculate_croak_chance;
+
+    #[test]
+    fn test_bust_rolls_add_up_to_croak_chance() {
+        let active = HashSet::from([2, 7, 12]);
+        let inactive = HashSet::from([6]);
+        let analysis = analyse_roll(&active, &inactive);
+        let ways: usize = analysis.bust_rolls.iter().map(|roll| roll.ways).sum();
+        assert_eq!(
+            ways as f64 / 1296.0,
+            calculate_croak_chance(&active, &inactive)
+        );
+        assert_eq!(analysis.odds.croak_chance, ways as f64 / 1296.0);
+        // Busts need no pair summing to 2, 7 or 12, e.g. no 1 & 6.
+        assert!(analysis
+            .bust_rolls
+            .iter()
+            .all(|roll| !roll.dice.contains(&1) || !roll.dice.contains(&6)));
+        assert!(analysis
+            .bust_rolls
+            .windows(2)
+            .all(|w| w[0].ways >= w[1].ways));
+    }
+}
@@ -0,0 +1,427 @@
+use serde::Serialize;
+use std::{cmp::Ordering, collections::HashSet};
+
+use super::{
+    logic::calculate_croak_chance, personality::Personality, player::PlayerMode, Choice, Column,
+    ColumnID, GameState,
+};
+
+/// Extra weight given to a choice that hops the same column twice.
+const DOUBLE_MULTIPLIER: f64 = 1.5;
+/// How far the risk threshold is pulled towards the croak chance modelled opponents stop at,
+/// to keep pace with bold players and take fewer chances against cautious ones.
+const RIVAL_PACE: f64 = 0.5;
+/// Runs an opponent has to have banked before the bots go by where they stop.
+const MODELLED_STOPS: usize = 5;
+
+/// Why a bot decided to hop or stop.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HopRationale {
+    /// Whether the bot will hop.
+    pub hop: bool,
+    /// Chance (0.0 - 1.0) the next roll has no playable options.
+    pub croak_chance: f64,
+    /// Croak chance the bot will risk, after its aversion to the hops made this run,
+    /// and pulled towards where its opponents stop.
+    pub risk_threshold: f64,
+    /// Croak chance the boldest modelled opponent usually stops at, if any are modelled.
+    pub rival_stop_chance: Option<f64>,
+    /// A column has been topped this run, so the bot stops to bank it whatever the risk.
+    pub topped: bool,
+}
+
+/// Attractiveness of a single column to a bot, broken down by factor.
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct ColumnScore {
+    /// Column (2d6) being scored.
+    pub col: ColumnID,
+    /// Bonus for a column already being climbed this run.
+    pub active: f64,
+    /// Reward for hops already invested in the column.
+    pub progress: f64,
+    /// Reward for being close to the top.
+    pub distance: f64,
+    /// Reward for blocking an opponent close to the top.
+    pub opponent: f64,
+    /// Reward for columns rolled more often.
+    pub probability: f64,
+    /// Reward for racing opponents on the columns they favour.
+    pub rival: f64,
+    /// Sum of the factors, never negative.
+    pub total: f64,
+}
+
+/// Score of one of the offered choices.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChoiceScore {
+    /// The choice (2d6) being scored.
+    pub choice: Choice,
+    /// Score of each column in the choice.
+    pub columns: Vec<ColumnScore>,
+    /// Weighting applied to the column scores, higher for doubles.
+    pub multiplier: f64,
+    /// Overall score, higher is better.
+    pub score: f64,
+}
+
+/// Why a bot picked one of the offered choices.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChoiceRationale {
+    /// The choice the bot made.
+    pub choice: Choice,
+    /// Position of the choice in the ranking, 0 unless the bot is holding back.
+    pub rank: usize,
+    /// All of the offered choices, best first.
+    pub ranked: Vec<ChoiceScore>,
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+struct EvaluateColumn {
+    /// How many hops to the top
+    banked_distance: usize,
+    /// How many of those hops are not secure
+    risked: usize,
+    /// Has reached the top but not banked yet
+    topped: bool,
+    // /// Closest Opponent
+    // opponent_distance: usize,
+}
+
+impl EvaluateColumn {
+    fn evaluate(
+        columns: [Column; 11],
+        active_cols: &HashSet<ColumnID>,
+        player_index: usize,
+    ) -> HashSet<Self> {
+        active_cols
+            .iter()
+            .map(|col| {
+                let index = *col - 2; // convert from 2d6 to index
+                let Some(column) = columns.get(index) else {
+                    panic!("Invalid column index {}", col);
+                };
+                let banked_distance = column.height - column.hops[player_index];
+                Self {
+                    banked_distance,
+                    risked: column.risked,
+                    topped: banked_distance <= column.risked,
+                }
+            })
+            .collect()
+    }
+}
+
+impl GameState {
+    /// The personality of the current player, panics if they are human.
+    fn bot_personality(&self) -> Personality {
+        let player = &self.settings.players[self.current_player];
+        if let PlayerMode::Adaptive = player.mode {
+            return Personality::adaptive(self.adaptive_strength());
+        }
+        let Some(personality) = player.mode.personality() else {
+            panic!("Shouldn't be called for a human player");
+        };
+        personality
+    }
+    /// Decide bot action for the current player, hop (true) or stop (false).
+    pub fn bot_should_continue(&self) -> bool {
+        self.bot_explain_continue().hop
+    }
+    /// Decide whether the current bot should hop, and why.
+    pub fn bot_explain_continue(&self) -> HopRationale {
+        self.explain_hop(&self.bot_personality())
+    }
+    /// Whether a player with the given personality should hop, and why.
+    pub fn explain_hop(&self, personality: &Personality) -> HopRationale {
+        let active_cols = self.get_selected();
+        let inactive_cols = self.get_unavailable();
+        let evaluation = EvaluateColumn::evaluate(self.columns, &active_cols, self.current_player);
+        let topped = evaluation.iter().any(|col| col.topped);
+        // croak chance 0.0 - 1.0
+        let croak_chance = calculate_croak_chance(&active_cols, &inactive_cols);
+        // Adjust threshold based on hops: subtract aversion factor for each hop made
+        let mut risk_threshold = personality.adjusted_risk_threshold(self.hops);
+        let rival_stop_chance = self.rival_stop_chance();
+        if let Some(stop_chance) = rival_stop_chance {
+            risk_threshold += (stop_chance - risk_threshold) * RIVAL_PACE;
+            risk_threshold = risk_threshold.max(personality.minimum_risk_threshold);
+        }
+        HopRationale {
+            // bank this progress if a column has been topped
+            hop: !topped && croak_chance < risk_threshold,
+            croak_chance,
+            risk_threshold,
+            rival_stop_chance,
+            topped,
+        }
+    }
+    /// Decide which of the offered column(s) the current bot player should select.
+    pub fn bot_choose(&self, choices: &HashSet<Choice>) -> Choice {
+        self.bot_explain_choice(choices).choice
+    }
+    /// Decide which of the offered column(s) the current bot should select, and why.
+    pub fn bot_explain_choice(&self, choices: &HashSet<Choice>) -> ChoiceRationale {
+        let ranked = self.rank_choices(&self.bot_personality(), choices);
+        // Choose the best option, unless holding back.
+        // Indexing is safe because options.choices is guaranteed non-empty by game logic,
+        // and the rank is never past the middle option.
+        let rank = self.bot_choice_rank(ranked.len());
+        ChoiceRationale {
+            choice: ranked[rank].choice,
+            rank,
+            ranked,
+        }
+    }
+    /// Score each of the offered column(s) for a player with the given personality, best first.
+    pub fn rank_choices(
+        &self,
+        personality: &Personality,
+        choices: &HashSet<Choice>,
+    ) -> Vec<ChoiceScore> {
+        let player_index = self.current_player;
+        // Get indices of all other players
+        let opponent_indices: Vec<usize> = (0..self.settings.players.len())
+            .filter(|&idx| idx != player_index)
+            .collect();
+        let active_cols = &self.get_selected();
+        let columns = self.columns;
+        let rate = |index: usize| {
+            let Some(column) = columns.get(index) else {
+                panic!("Invalid index {}", index);
+            };
+            let rival_preference = self.rival_preference(column.col, &opponent_indices);
+            column.rate(
+                personality,
+                active_cols,
+                player_index,
+                &opponent_indices,
+                rival_preference,
+            )
+        };
+        let mut ranked: Vec<ChoiceScore> = choices
+            .iter()
+            .map(|&choice| {
+                // convert number to index, as the backend uses 0-based indexing and the user is
+                // choosing a 2d6 number, so we need to subtract 2 from the number.
+                let indexed_choice = (choice.0 - 2, choice.1.map(|x| x - 2));
+                let columns = match indexed_choice {
+                    (first, None) => vec![rate(first)],
+                    (first, Some(second)) => vec![rate(first), rate(second)],
+                };
+                // Sum the attractiveness of both columns for pairs,
+                // weighted higher if the two values are the same.
+                let multiplier = match indexed_choice {
+                    (first, Some(second)) if first == second => DOUBLE_MULTIPLIER,
+                    _ => 1.0,
+                };
+                let score = columns.iter().map(|col| col.total).sum::<f64>() * multiplier;
+                ChoiceScore {
+                    choice,
+                    columns,
+                    multiplier,
+                    score,
+                }
+            })
+            .collect();
+        // Sort descending: higher score is better. Ties go to the lowest columns,
+        // so the choice doesn't depend on the order of the options.
+        ranked.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then(a.choice.cmp(&b.choice))
+        });
+        ranked
+    }
+    /// Croak chance the boldest of the current player's modelled opponents usually stops at,
+    /// None until one of them has banked enough runs to go by.
+    fn rival_stop_chance(&self) -> Option<f64> {
+        self.opponent_models
+            .iter()
+            .filter(|(&idx, model)| idx != self.current_player && model.banked >= MODELLED_STOPS)
+            .map(|(_, model)| model.stop_chance)
+            .reduce(f64::max)
+    }
+    /// How much the keenest of the modelled opponents favours a column, 1.0 is average.
+    fn rival_preference(&self, col: ColumnID, opponent_indices: &[usize]) -> f64 {
+        opponent_indices
+            .iter()
+            .filter_map(|idx| self.opponent_models.get(idx))
+            .map(|model| model.preference(col))
+            .fold(1.0, f64::max)
+    }
+    /// Which of the `options`, ranked best first, the current bot should take.
+    /// Adaptive bots playing weakly pass over the best options, down to the middle one.
+    fn bot_choice_rank(&self, options: usize) -> usize {
+        match self.settings.players[self.current_player].mode {
+            PlayerMode::Adaptive => {
+                let middle = options.saturating_sub(1) as f64 / 2.0;
+                ((1.0 - self.adaptive_strength()) * middle).round() as usize
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl Column {
+    /// Evaluate the attractiveness of choosing this column on an arbitrary scale, by factor.
+    /// Higher is better.
+    ///
+    /// # Arguments
+    /// * `personality` - Weights used to score each factor.
+    /// * `active_cols` - Set of column IDs already selected in the current turn.
+    /// * `player_index` - Index of the current AI player.
+    /// * `opponent_indices` - Indices of all opponent players.
+    /// * `rival_preference` - How much modelled opponents favour this column, 1.0 is average.
+    fn rate(
+        &self,
+        personality: &Personality,
+        active_cols: &HashSet<ColumnID>,
+        player_index: usize,
+        opponent_indices: &[usize],
+        rival_preference: f64,
+    ) -> ColumnScore {
+        let mut score = ColumnScore {
+            col: self.col,
+            ..Default::default()
+        };
+
+        // --- Calculations ---
+
+        // 1. Active Column Bonus: Prioritize using columns already started this turn
+        //    if we haven't picked 3 unique columns yet.
+        if active_cols.len() < 3 && active_cols.contains(&self.col) {
+            score.active = personality.weight_active;
+        }
+
+        let current_hops = self.hops[player_index];
+        // Calculate state *after* the potential move (adding one hop).
+        let potential_hops = current_hops + 1;
+        let potential_distance = self.height.saturating_sub(potential_hops);
+
+        // 3. Progress Score: Reward columns where we've already invested hops.
+        score.progress = personality.weight_progress * (current_hops as f64);
+
+        // 4. Distance Score: Reward columns closer to the top. Higher score for smaller potential_distance.
+        //    Add 1.0 to denominator to avoid division by zero if topped (potential_distance is 0).
+        //    Scale by height to give slightly more weight to finishing taller columns.
+        score.distance =
+            personality.weight_distance * (self.height as f64 / (potential_distance as f64 + 1.0));
+
+        // 5. Opponent Blocking Score: Consider blocking opponents close to winning this column.
+        let max_opponent_hops = opponent_indices
+            .iter()
+            .map(|&idx| self.hops[idx])
+            .max()
+            .unwrap_or(0); // Find the most advanced opponent on this column
+
+        if max_opponent_hops > 0 {
+            let opponent_distance = self.height.saturating_sub(max_opponent_hops);
+            // Add score, weighted more heavily if the opponent is closer (smaller opponent_distance).
+            score.opponent = personality.weight_opponent
+                * (self.height as f64 / (opponent_distance as f64 + 1.0));
+        }
+
+        // 6. Column Probability Score: Give a slight edge to columns corresponding to more probable dice rolls.
+        //    Maps column ID (0-10) to dice sum probability factor (1-6).
+        //    IDs 0/10 (sum 2/12) -> factor 1; ID 5 (sum 7) -> factor 6.
+        let probability_factor = 6.0 - (5.0 - self.col as f64).abs(); // Simple way to get 1..6..1 pattern
+        score.probability = personality.weight_probability * probability_factor;
+
+        // 7. Rival Preference Score: Race opponents on the columns they are known to favour.
+        score.rival = personality.weight_rival * (rival_preference - 1.0).max(0.0);
+
+        // Ensure score is non-negative
+        score.total = (score.active
+            + score.progress
+            + score.distance
+            + score.opponent
+            + score.probability
+            + score.rival)
+            .max(0.0);
+        score
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{History, OpponentModel, Player, RunOutcome, SettingsState};
+
+    fn game(mode: PlayerMode) -> GameState {
+        let players = (0..2)
+            .map(|id| Player::new(id, mode, format!("Bot {id}")))
+            .collect();
+        let mut game = GameState::default();
+        game.new_game(SettingsState::new(players, 3));
+        game
+    }
+
+    #[test]
+    fn test_rationale_explains_choice() {
+        let mut game = game(PlayerMode::Normal);
+        game.risk_columns(5, Some(5)).unwrap(); // two hops up column 7
+        let choices = HashSet::from([(2, Some(12)), (7, None), (6, Some(8))]);
+        let rationale = game.bot_explain_choice(&choices);
+        assert_eq!(rationale.choice, game.bot_choose(&choices));
+        assert_eq!(rationale.ranked.len(), choices.len());
+        for option in &rationale.ranked {
+            for col in &option.columns {
+                let factors = col.active
+                    + col.progress
+                    + col.distance
+                    + col.opponent
+                    + col.probability
+                    + col.rival;
+                assert_eq!(col.total, factors.max(0.0));
+            }
+        }
+        let hop = game.bot_explain_continue();
+        assert_eq!(hop.hop, game.bot_should_continue());
+        assert_eq!(hop.croak_chance, 0.0); // only column 7 is being climbed
+    }
+
+    #[test]
+    fn test_bot_rolls_before_banking() {
+        let mut game = game(PlayerMode::Safe);
+        // with 5 - 11 locked, even the first roll is likely to croak,
+        // but there's nothing to lose by it
+        for column in &mut game.columns[3..10] {
+            column.locked = Some(1);
+        }
+        let hop = game.bot_explain_continue();
+        assert_eq!(hop.croak_chance, 0.0);
+        assert!(hop.hop, "there's nothing to bank before the first roll");
+        assert!(game.analyse_roll().odds.croak_chance > hop.risk_threshold);
+
+        // a run that ends without a roll is still recorded
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        history.next_player(RunOutcome::Banked, game.get_unavailable());
+        assert_eq!(history.players[0].runs()[0].outcome, RunOutcome::Banked);
+    }
+
+    #[test]
+    fn test_bot_keeps_pace_with_bold_opponents() {
+        let mut game = game(PlayerMode::Safe);
+        game.risk_columns(5, None).unwrap();
+        let alone = game.bot_explain_continue();
+        assert_eq!(alone.rival_stop_chance, None);
+
+        let bold = OpponentModel {
+            banked: MODELLED_STOPS,
+            stop_chance: 0.6,
+            ..Default::default()
+        };
+        game.opponent_models.insert(1, bold.clone());
+        let paced = game.bot_explain_continue();
+        assert_eq!(paced.rival_stop_chance, Some(0.6));
+        assert!(paced.risk_threshold > alone.risk_threshold);
+        assert!(paced.risk_threshold < 0.6);
+
+        // only opponents count
+        game.opponent_models.clear();
+        game.opponent_models.insert(0, bold);
+        assert_eq!(game.bot_explain_continue().rival_stop_chance, None);
+    }
+}
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri_plugin_store::Store;
+
+use super::{
+    player::{Player, PlayerMode},
+    save,
+    saves::now,
+    stats::{History, StatsSummary},
+    ColumnID, GameState, PlayerID,
+};
+
+/// Number of favourite columns kept in a player's career.
+const FAVOURITE_COLUMNS: usize = 3;
+
+/// A finished game, kept so players' careers outlast it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedGame {
+    /// When the game finished, in seconds since the Unix epoch.
+    pub finished: u64,
+    pub players: Vec<Player>,
+    /// Index of the player who won.
+    pub winner: PlayerID,
+    pub history: History,
+    pub summary: StatsSummary,
+}
+
+/// Every finished game, oldest first.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GameArchive(pub Vec<ArchivedGame>);
+
+/// Games played and won against some opponent.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WinRecord {
+    pub played: usize,
+    pub won: usize,
+}
+
+/// How a player got on in one of their games.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareerGame {
+    /// When the game finished, in seconds since the Unix epoch.
+    pub finished: u64,
+    pub won: bool,
+    /// Share of runs that croaked (0.0 - 1.0).
+    pub croak_rate: f64,
+    pub luck: f64,
+}
+
+/// A player's statistics over every game they've finished.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CareerStats {
+    /// Name the player last played under.
+    pub name: String,
+    pub games_played: usize,
+    pub games_won: usize,
+    /// Record against each kind of bot, by mode.
+    pub vs_bots: BTreeMap<String, WinRecord>,
+    /// Average share of runs that croaked (0.0 - 1.0).
+    pub croak_rate: f64,
+    /// Average luck, see `PlayerStats::luck`.
+    pub luck: f64,
+    /// Each game, oldest first, to chart croak rate and luck over time.
+    pub games: Vec<CareerGame>,
+    /// Columns (2 - 12) the player chooses most, with how often they chose them.
+    pub favourite_columns: Vec<(ColumnID, usize)>,
+}
+
+impl ArchivedGame {
+    /// Archive a game that has been won.
+    pub fn new(state: &GameState, history: History, summary: StatsSummary) -> Option<Self> {
+        let winner = state.winner.as_ref()?;
+        let players = state.settings.players.clone();
+        Some(Self {
+            finished: now(),
+            winner: players.iter().position(|player| player.id == winner.id)?,
+            players,
+            history,
+            summary,
+        })
+    }
+}
+
+impl GameArchive {
+    /// Keep a finished game.
+    pub fn record(&mut self, game: ArchivedGame) {
+        self.0.push(game);
+    }
+    /// Careers of the humans and profiled players, by profile id or name.
+    pub fn careers(&self) -> BTreeMap<String, CareerStats> {
+        let mut careers = BTreeMap::new();
+        let mut columns: HashMap<&str, HashMap<ColumnID, usize>> = HashMap::new();
+        for game in &self.0 {
+            for (index, player) in game.players.iter().enumerate() {
+                if player.profile.is_none() && !matches!(player.mode, PlayerMode::Human) {
+                    continue;
+                }
+                let career: &mut CareerStats = careers.entry(player.key().to_string()).or_default();
+                let won = index == game.winner;
+                career.name = player.name.clone();
+                career.games_played += 1;
+                career.games_won += won as usize;
+                let bots = game.players.iter().filter(|bot| bot.id != player.id);
+                for bot in bots.filter(|bot| bot.mode.personality().is_some()) {
+                    let record = career.vs_bots.entry(bot.mode.to_string()).or_default();
+                    record.played += 1;
+                    record.won += won as usize;
+                }
+                if let Some(stats) = game.summary.player_stats.get(index) {
+                    let runs = stats.croaked + stats.banked;
+                    career.games.push(CareerGame {
+                        finished: game.finished,
+                        won,
+                        croak_rate: match runs {
+                            0 => 0.0,
+                            runs => stats.croaked as f64 / runs as f64,
+                        },
+                        luck: stats.luck,
+                    });
+                }
+                let chosen = columns.entry(player.key()).or_default();
+                let runs = game
+                    .history
+                    .players
+                    .get(index)
+                    .map_or(&[][..], |p| p.runs());
+                for (first, second) in runs
+                    .iter()
+                    .flat_map(|run| &run.turns)
+                    .flat_map(|t| t.chosen)
+                {
+                    // convert from index to 2d6
+                    *chosen.entry(first + 2).or_default() += 1;
+                    if let Some(second) = second {
+                        *chosen.entry(second + 2).or_default() += 1;
+                    }
+                }
+            }
+        }
+        for (key, career) in careers.iter_mut() {
+            let games = career.games.len().max(1) as f64;
+            career.croak_rate = career.games.iter().map(|g| g.croak_rate).sum::<f64>() / games;
+            career.luck = career.games.iter().map(|g| g.luck).sum::<f64>() / games;
+            let mut favourites: Vec<(ColumnID, usize)> = columns
+                .remove(key.as_str())
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            favourites.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            favourites.truncate(FAVOURITE_COLUMNS);
+            career.favourite_columns = favourites;
+        }
+        careers
+    }
+    /// Update the archive from disk. If it can't be loaded, it's backed up
+    /// and starts afresh instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "archive") {
+            Ok(archive) => *self = archive.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save the archive to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "archive", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{player::PlayerStats, DiceResult, RunOutcome};
+    use std::collections::HashSet;
+
+    /// A finished game where the first player banked a run on 7 & 9, then the second croaked.
+    fn finished_game(players: Vec<Player>, winner: PlayerID) -> ArchivedGame {
+        let mut state = GameState::default();
+        state.settings.players = players;
+        state.winner = Some(state.settings.players[winner].clone());
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        history
+            .player_mut()
+            .record_roll(&DiceResult::default(), &HashSet::new());
+        history.player_mut().record_choice(5, Some(7));
+        history.next_player(RunOutcome::Banked, HashSet::new());
+        history
+            .player_mut()
+            .record_roll(&DiceResult::default(), &HashSet::new());
+        history.next_player(RunOutcome::Croaked, HashSet::new());
+        let stats = |croaked, banked| PlayerStats {
+            longest_run: 1,
+            croaked,
+            banked,
+            luck: 0.1,
+        };
+        let summary = StatsSummary {
+            player_stats: vec![stats(0, 1), stats(1, 0)],
+            most_contested_column: 5,
+            total_turns: 2,
+            win_timeline: vec![],
+        };
+        ArchivedGame::new(&state, history, summary).unwrap()
+    }
+
+    #[test]
+    fn test_careers_span_games() {
+        let mut grandma = GameState::default().settings.players[0].clone();
+        grandma.profile = Some("grandma".into());
+        let mut bot = GameState::default().settings.players[1].clone();
+        bot.mode = PlayerMode::Risky;
+        let mut archive = GameArchive::default();
+        archive.record(finished_game(vec![grandma.clone(), bot.clone()], 0));
+        archive.record(finished_game(vec![grandma, bot], 1));
+
+        let careers = archive.careers();
+        // bots without a profile don't have a career
+        assert_eq!(careers.len(), 1);
+        let career = &careers["grandma"];
+        assert_eq!((career.games_played, career.games_won), (2, 1));
+        assert_eq!(career.vs_bots["Risky"].won, 1);
+        assert_eq!(career.croak_rate, 0.0);
+        assert_eq!(career.games.len(), 2);
+        assert_eq!(career.favourite_columns, vec![(7, 2), (9, 2)]);
+    }
+}
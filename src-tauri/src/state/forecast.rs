@@ -0,0 +1,137 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::{
+    player::PlayerMode,
+    simulation::{Estimate, Simulation},
+    stats::History,
+    GameState,
+};
+
+/// Playouts for a live estimate of each player's chance of winning.
+pub const DEFAULT_PLAYOUTS: usize = 400;
+/// Most playouts an estimate will run, as each one plays out a whole game.
+pub const MAX_PLAYOUTS: usize = 10_000;
+/// Bot that plays for the humans in the playouts.
+const REFERENCE_BOT: PlayerMode = PlayerMode::Normal;
+
+/// Each player's chance of winning from a position in the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinChances {
+    /// Chance of winning for each player, by player index.
+    pub chances: Vec<Estimate>,
+    /// Games played out to make the estimate.
+    pub playouts: usize,
+    /// Playouts that hit the run limit without a winner.
+    pub unfinished: usize,
+}
+
+impl WinChances {
+    /// Chance (0.0 - 1.0) of each player winning, by player index.
+    pub fn values(&self) -> Vec<f64> {
+        self.chances.iter().map(|chance| chance.value).collect()
+    }
+}
+
+impl GameState {
+    /// Estimate each player's chance of winning by playing the rest of the game out
+    /// `playouts` times, with bots playing as themselves and the reference bot for humans.
+    /// The current player picks up their run where it is. Seeded for reproducibility.
+    pub fn win_chances(&self, playouts: usize, seed: u64) -> anyhow::Result<WinChances> {
+        if !(1..=MAX_PLAYOUTS).contains(&playouts) {
+            return Err(anyhow!(
+                "Can only play out 1 to {} games, not {}",
+                MAX_PLAYOUTS,
+                playouts
+            ));
+        }
+        let count = self.settings.players.len();
+        if self.winner.is_some() {
+            // the winner is left as the current player when the game ends
+            let chances = (0..count)
+                .map(|player| {
+                    let value = (player == self.current_player) as usize as f64;
+                    Estimate {
+                        value,
+                        low: value,
+                        high: value,
+                    }
+                })
+                .collect();
+            return Ok(WinChances {
+                chances,
+                playouts: 0,
+                unfinished: 0,
+            });
+        }
+        let mut game = self.clone();
+        for player in game.settings.players.iter_mut() {
+            if matches!(player.mode, PlayerMode::Human) {
+                player.mode = REFERENCE_BOT;
+            }
+        }
+        let mut wins = vec![0; count];
+        let mut unfinished = 0;
+        for playout in 0..playouts {
+            // The playouts don't need the story so far, just somewhere to record the rest.
+            let mut history = History::default();
+            history.new_game(count)?;
+            history.current_player = game.current_player;
+            history.player_mut().record_start_run(HashSet::new());
+            let seed = seed.wrapping_add(playout as u64);
+            match Simulation::from_state(game.clone(), history, seed)?
+                .play()
+                .winner
+            {
+                Some(winner) => wins[winner] += 1,
+                None => unfinished += 1,
+            }
+        }
+        Ok(WinChances {
+            chances: wins
+                .into_iter()
+                .map(|won| Estimate::proportion(won, playouts))
+                .collect(),
+            playouts,
+            unfinished,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::state::human_game;
+
+    #[test]
+    fn test_win_chances_favour_the_leader() {
+        let mut game = human_game(2, 2);
+        // The second player is a hop from the top of 6 & 8, and has already won 7.
+        game.columns[4].hops[1] = 10;
+        game.columns[6].hops[1] = 10;
+        game.columns[5].hops[1] = 13;
+        game.columns[5].locked = Some(1);
+        game.settings.players[1].won_cols.push(7);
+
+        let estimate = game.win_chances(200, 1).unwrap();
+        assert_eq!(
+            estimate.values(),
+            game.win_chances(200, 1).unwrap().values()
+        );
+        let total: f64 = estimate.values().iter().sum();
+        assert!((total + estimate.unfinished as f64 / 200.0 - 1.0).abs() < 1e-9);
+        assert!(estimate.chances[1].value > 0.8);
+        assert!(game.win_chances(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_win_chances_for_four_players() {
+        let game = human_game(4, 3);
+        for seed in 0..5 {
+            let estimate = game.win_chances(50, seed).unwrap();
+            assert_eq!(estimate.chances.len(), 4);
+            let total: f64 = estimate.values().iter().sum();
+            assert!((total + estimate.unfinished as f64 / 50.0 - 1.0).abs() < 1e-9);
+        }
+    }
+}
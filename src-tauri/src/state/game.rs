@@ -1,12 +1,22 @@
-use std::{collections::HashSet, fmt::Debug, sync::Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::Mutex,
+};
 
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use tauri_plugin_store::Store;
 
 use super::{
+    achievement::Unlock,
+    adaptive::DEFAULT_SKILL,
     columns::{generate_columns, Column},
+    opponent::OpponentModel,
     player::{Player, PlayerMode, RunOutcome},
-    SettingsState,
+    save,
+    training::Feedback,
+    PlayerID, SettingsState,
 };
 
 pub type GameStateMutex = Mutex<GameState>;
@@ -22,6 +32,22 @@ pub struct GameState {
     /// Columns selected in the current hop sequence (indices into `columns`)
     pub columns: [Column; 11],
     pub winner: Option<Player>,
+    /// Humans' recent skill against bots (0.0 - 1.0), which adaptive bots start from.
+    #[serde(default = "default_skill")]
+    pub adaptive_skill: f64,
+    /// What bots have learnt about each human player, by player index.
+    #[serde(default)]
+    pub opponent_models: HashMap<PlayerID, OpponentModel>,
+    /// Grade of the last decision a human made, in training mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<Feedback>,
+    /// Achievements unlocked with the run that has just finished.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocked: Vec<Unlock>,
+}
+
+fn default_skill() -> f64 {
+    DEFAULT_SKILL
 }
 
 impl Debug for GameState {
@@ -55,6 +81,10 @@ impl GameState {
             hops: 0,
             columns: generate_columns(),
             winner: None,
+            adaptive_skill: self.adaptive_skill,
+            opponent_models: self.opponent_models.to_owned(),
+            feedback: None,
+            unlocked: vec![],
         }
     }
     /// Lock in any risked moves for the current player and
@@ -81,6 +111,21 @@ impl GameState {
         self.hops = 0;
         self.current_player = (self.current_player + 1) % self.settings.players.len();
     }
+    /// Risk a hop on one or two columns (indices into `columns`) for the current player.
+    pub fn risk_columns(&mut self, first: usize, second: Option<usize>) -> anyhow::Result<()> {
+        let Some(col1) = self.columns.get_mut(first) else {
+            return Err(anyhow!("Invalid column index {}", first));
+        };
+        col1.risked += 1;
+        if let Some(second) = second {
+            let Some(col2) = self.columns.get_mut(second) else {
+                return Err(anyhow!("Invalid column index {}", second));
+            };
+            col2.risked += 1;
+        };
+        self.hops += 1;
+        Ok(())
+    }
     /// Return a list of the selected columns this run.
     pub fn get_selected(&self) -> HashSet<usize> {
         self.columns
@@ -105,13 +150,7 @@ impl GameState {
                 self.winner = Some(player.clone());
             }
         }
-        match self.winner.as_ref() {
-            Some(winner) => {
-                println!("Game Over! Player {} wins!", winner.name);
-                true
-            }
-            None => false,
-        }
+        self.winner.is_some()
     }
     /// Check if a player is sat at the top of an unlocked column.
     /// If so they win the column and it is locked.
@@ -126,20 +165,25 @@ impl GameState {
             }
         }
     }
-    /// Update game state from disk
-    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) {
-        if let Some(state) = store.get("state") {
-            *self = serde_json::from_value(state).unwrap_or_default();
-        } else {
-            println!("'state' missing from store");
-            *self = Self::default();
+    /// Update game state from disk, migrating it from older save versions.
+    /// If it can't be loaded, it's backed up and a fresh game state is used instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "state") {
+            Ok(Some(state)) => *self = state,
+            Ok(None) => {
+                println!("'state' missing from store");
+                *self = Self::default();
+            }
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
         }
+        Ok(())
     }
     /// Save game state to disk
     pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
-        let state = serde_json::to_value(self.clone())?;
-        store.set("state", state);
-        Ok(())
+        save::save(store, "state", self)
     }
     /// Set up a new game state
     pub fn new_game(&mut self, mut settings: SettingsState) {
@@ -159,25 +203,20 @@ impl Default for GameState {
             in_progress: false,
             settings: SettingsState {
                 players: vec![
-                    Player {
-                        mode: PlayerMode::Human,
-                        id: 0,
-                        name: "Player 1".to_string(),
-                        won_cols: vec![],
-                    },
-                    Player {
-                        mode: PlayerMode::Human,
-                        id: 1,
-                        name: "Player 2".to_string(),
-                        won_cols: vec![],
-                    },
+                    Player::new(0, PlayerMode::Human, "Player 1".to_string()),
+                    Player::new(1, PlayerMode::Human, "Player 2".to_string()),
                 ],
                 win_cols: 3,
+                training: false,
             },
             current_player: 0,
             hops: 0,
             columns: generate_columns(),
             winner: None,
+            adaptive_skill: DEFAULT_SKILL,
+            opponent_models: HashMap::new(),
+            feedback: None,
+            unlocked: vec![],
         }
     }
 }
@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::{
+    bot::{ChoiceScore, ColumnScore, HopRationale},
+    personality::Personality,
+    Choice, GameState,
+};
+
+/// Hints follow the Risky bot, the strongest of the built-in bots in tournaments.
+const HINT_PERSONALITY: Personality = Personality::RISKY;
+
+/// Advice for the current player: what the frog would do in their position.
+#[derive(Debug, Clone, Serialize)]
+pub struct Hint {
+    /// Whether to hop (roll again) rather than stop.
+    pub hop: bool,
+    /// Why to hop or stop.
+    pub hop_reason: String,
+    /// Numbers behind the hop advice.
+    pub rationale: HopRationale,
+    /// Offered choices, best first. Empty if there is no roll to choose from.
+    pub choices: Vec<ChoiceHint>,
+}
+
+/// One of the offered choices, ranked for a hint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChoiceHint {
+    /// The choice (2d6).
+    pub choice: Choice,
+    /// The main reasons to take it.
+    pub reason: String,
+    /// Score behind the ranking, broken down by factor.
+    pub score: ChoiceScore,
+}
+
+impl ColumnScore {
+    /// Each of the factors with a description, for explaining the score.
+    fn factors(&self) -> [(&'static str, f64); 6] {
+        [
+            ("keeps to columns you're already climbing", self.active),
+            ("builds on hops you've already made", self.progress),
+            ("gets you closer to the top", self.distance),
+            ("blocks an opponent close to the top", self.opponent),
+            ("uses numbers that are rolled often", self.probability),
+            ("races opponents on the columns they favour", self.rival),
+        ]
+    }
+}
+
+impl ChoiceHint {
+    fn new(score: ChoiceScore) -> Self {
+        // Total each factor over the columns in the choice.
+        let mut factors = ColumnScore::default().factors();
+        for column in &score.columns {
+            for (total, (_, value)) in factors.iter_mut().zip(column.factors()) {
+                total.1 += value;
+            }
+        }
+        factors.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut reasons: Vec<&str> = factors
+            .iter()
+            .take(2)
+            .filter(|(_, value)| *value > 0.0)
+            .map(|(reason, _)| *reason)
+            .collect();
+        if score.multiplier > 1.0 {
+            reasons.insert(0, "hops the same column twice");
+        }
+        let reason = match reasons.as_slice() {
+            [] => "no clear advantage".to_string(),
+            [only] => only.to_string(),
+            [first @ .., last] => format!("{} and {}", first.join(", "), last),
+        };
+        Self {
+            choice: score.choice,
+            reason,
+            score,
+        }
+    }
+}
+
+impl GameState {
+    /// What the current player should do, and which of the offered `choices`, if any, to take.
+    pub fn hint(&self, choices: Option<&HashSet<Choice>>) -> Hint {
+        let rationale = self.explain_hop(&HINT_PERSONALITY);
+        let croak = rationale.croak_chance * 100.0;
+        let threshold = rationale.risk_threshold * 100.0;
+        let hop_reason = if rationale.topped {
+            "You've reached the top of a column, stop now to claim it.".to_string()
+        } else if rationale.hop {
+            format!(
+                "Only a {:.0}% chance of croaking, worth risking up to {:.0}% after {} hops.",
+                croak, threshold, self.hops
+            )
+        } else {
+            format!(
+                "A {:.0}% chance of croaking is too risky after {} hops, bank your progress.",
+                croak, self.hops
+            )
+        };
+        let choices = choices
+            .map(|choices| self.rank_choices(&HINT_PERSONALITY, choices))
+            .unwrap_or_default()
+            .into_iter()
+            .map(ChoiceHint::new)
+            .collect();
+        Hint {
+            hop: rationale.hop,
+            hop_reason,
+            rationale,
+            choices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::human_game;
+
+    #[test]
+    fn test_hint_ranks_choices() {
+        let game = human_game(2, 3);
+
+        let hint = game.hint(None);
+        assert!(hint.hop); // nothing to lose at the start of a run
+        assert!(hint.choices.is_empty());
+
+        let choices = HashSet::from([(2, Some(12)), (7, Some(7))]);
+        let hint = game.hint(Some(&choices));
+        assert_eq!(hint.choices.len(), 2);
+        assert_eq!(hint.choices[0].choice, (7, Some(7)));
+        assert!(hint.choices[0]
+            .reason
+            .starts_with("hops the same column twice"));
+    }
+}
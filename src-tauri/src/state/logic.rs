@@ -1,10 +1,20 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use super::{Choice, ColumnID};
 
+const DICE_SIDES: usize = 6;
+const DICE_COUNT: usize = 4;
+
 // Total number of possible outcomes when rolling 4 six-sided dice.
 const TOTAL_ROLLS: f64 = 6. * 6. * 6. * 6.; // 1296
 
+/// Roll 4 dice, each a random number between 1 and 6 (inclusive).
+pub fn throw_dice<R: Rng + ?Sized>(rng: &mut R) -> [usize; DICE_COUNT] {
+    [0; DICE_COUNT].map(|_| rng.random_range(1..=DICE_SIDES))
+}
+
 /// Evaluate the available moves from the four dice.
 /// - They must be paired up and cannot be reused.
 /// - `selected` is the set of columns already picked this round (0–3 of them).
@@ -70,38 +80,125 @@ pub fn evaluate_moves(
     moves
 }
 
+/// Every possible roll of the four dice, each equally likely.
+pub(super) fn all_rolls() -> impl Iterator<Item = [usize; DICE_COUNT]> {
+    (0..TOTAL_ROLLS as usize).map(|roll| {
+        [
+            roll % DICE_SIDES + 1,
+            roll / DICE_SIDES % DICE_SIDES + 1,
+            roll / (DICE_SIDES * DICE_SIDES) % DICE_SIDES + 1,
+            roll / (DICE_SIDES * DICE_SIDES * DICE_SIDES) + 1,
+        ]
+    })
+}
+
+/// Sums of every pair of dice. The three pairings between them use every pair once.
+pub(super) fn pair_sums([d1, d2, d3, d4]: [usize; DICE_COUNT]) -> [usize; 6] {
+    [d1 + d2, d1 + d3, d1 + d4, d2 + d3, d2 + d4, d3 + d4]
+}
+
+/// Columns (by 2d6 value) that `evaluate_moves` would offer on their own if a pair of dice
+/// summed to them: not won, and either already active or there's room for a new one.
+pub(super) fn playable_columns(
+    active_cols: &HashSet<ColumnID>,
+    inactive_cols: &HashSet<ColumnID>,
+) -> [bool; 13] {
+    let has_room = active_cols.len() < 3;
+    let mut playable = [false; 13];
+    for (col, playable) in playable.iter_mut().enumerate().skip(2) {
+        *playable = !inactive_cols.contains(&col) && (has_room || active_cols.contains(&col));
+    }
+    playable
+}
+
 /// Calculate from game state what the likelihood is of going bust on the next roll.
 pub fn calculate_croak_chance(
     active_cols: &HashSet<ColumnID>,
     inactive_cols: &HashSet<ColumnID>,
 ) -> f64 {
-    // If no columns are active, you cannot bust based on column availability.
-    // Your evaluate_moves should ideally handle this, but an explicit check is safer.
+    // If no columns are active, there's no progress to lose by going bust.
     if active_cols.is_empty() {
         return 0.0;
     }
+    bust_chance(active_cols, inactive_cols)
+}
 
-    let mut bust_rolls = 0;
+/// Chance of the next roll having no move at all, even before any columns are active.
+fn bust_chance(active_cols: &HashSet<ColumnID>, inactive_cols: &HashSet<ColumnID>) -> f64 {
+    // A roll has a move if any pair of dice sums to a playable column.
+    let playable = playable_columns(active_cols, inactive_cols);
+    let bust_rolls = all_rolls()
+        .filter(|&dice| !pair_sums(dice).iter().any(|&sum| playable[sum]))
+        .count();
 
-    // Iterate through all possible outcomes of rolling 4 dice (d1, d2, d3, d4)
-    for d1 in 1..=6 {
-        for d2 in 1..=6 {
-            for d3 in 1..=6 {
-                for d4 in 1..=6 {
-                    let dice = [d1, d2, d3, d4];
+    // The probability is the number of bust outcomes divided by the total possible outcomes.
+    bust_rolls as f64 / TOTAL_ROLLS
+}
 
-                    // Call your evaluate_moves function. It determines if *any* valid move exists.
-                    // We assume it returns an empty collection if and only if the roll results in a bust.
-                    if evaluate_moves(dice, active_cols, inactive_cols).is_empty() {
-                        bust_rolls += 1;
-                    }
-                }
-            }
+/// Odds for the next roll, with the given columns active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollOdds {
+    /// Chance (0.0 - 1.0) of going bust, including on the first roll of a run.
+    pub croak_chance: f64,
+    /// Chance (0.0 - 1.0) of each column being offered, by column index.
+    pub hit_chances: [f64; 11],
+}
+
+/// Calculate the chance of going bust on the next roll, and of each column being offered.
+pub fn calculate_roll_odds(
+    active_cols: &HashSet<ColumnID>,
+    inactive_cols: &HashSet<ColumnID>,
+) -> RollOdds {
+    let playable = playable_columns(active_cols, inactive_cols);
+    let mut hit_rolls = [0usize; 13];
+    for dice in all_rolls() {
+        let mut hit = [false; 13];
+        for sum in pair_sums(dice) {
+            hit[sum] |= playable[sum];
+        }
+        for (rolls, hit) in hit_rolls.iter_mut().zip(hit) {
+            *rolls += hit as usize;
         }
     }
+    let mut hit_chances = [0.0; 11];
+    for (chance, rolls) in hit_chances.iter_mut().zip(&hit_rolls[2..]) {
+        *chance = *rolls as f64 / TOTAL_ROLLS;
+    }
+    RollOdds {
+        croak_chance: bust_chance(active_cols, inactive_cols),
+        hit_chances,
+    }
+}
 
-    // The probability is the number of bust outcomes divided by the total possible outcomes.
-    bust_rolls as f64 / TOTAL_ROLLS
+/// Odds the player would face on the next roll after taking one of the offered choices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChoicePreview {
+    /// The choice (2d6) being previewed.
+    pub choice: Choice,
+    #[serde(flatten)]
+    pub odds: RollOdds,
+}
+
+/// Preview the next roll after taking each of the `choices`, ordered by choice.
+pub fn preview_choices(
+    choices: &HashSet<Choice>,
+    active_cols: &HashSet<ColumnID>,
+    inactive_cols: &HashSet<ColumnID>,
+) -> Vec<ChoicePreview> {
+    let mut previews: Vec<ChoicePreview> = choices
+        .iter()
+        .map(|&choice| {
+            let mut active_cols = active_cols.clone();
+            active_cols.insert(choice.0);
+            active_cols.extend(choice.1);
+            ChoicePreview {
+                choice,
+                odds: calculate_roll_odds(&active_cols, inactive_cols),
+            }
+        })
+        .collect();
+    previews.sort_by_key(|preview| preview.choice);
+    previews
 }
 
 #[cfg(test)]
@@ -205,4 +302,64 @@ mod test {
         let inactive = HashSet::new();
         assert_approx_eq!(calculate_croak_chance(&active, &inactive), 0.0);
     }
+
+    #[test]
+    fn test_croak_chance_six_seven_eight() {
+        // The classic safest trio, 92% chance to roll at least one of them.
+        let active = HashSet::from([6, 7, 8]);
+        let inactive = HashSet::new();
+        assert_approx_eq!(
+            calculate_croak_chance(&active, &inactive),
+            104.0 / TOTAL_ROLLS
+        );
+    }
+
+    #[test]
+    fn test_croak_chance_matches_evaluate_moves() {
+        let cases: [(&[ColumnID], &[ColumnID]); 5] = [
+            (&[2], &[]),
+            (&[2, 12], &[7]),
+            (&[2, 3, 12], &[]),
+            (&[4, 7, 10], &[6, 8]),
+            (&[5, 9], &[2, 3, 4, 6, 7, 8, 10, 11, 12]),
+        ];
+        for (active, inactive) in cases {
+            let active: HashSet<ColumnID> = active.iter().copied().collect();
+            let inactive: HashSet<ColumnID> = inactive.iter().copied().collect();
+            let bust_rolls = all_rolls()
+                .filter(|&dice| evaluate_moves(dice, &active, &inactive).is_empty())
+                .count();
+            assert_approx_eq!(
+                calculate_croak_chance(&active, &inactive),
+                bust_rolls as f64 / TOTAL_ROLLS
+            );
+        }
+    }
+
+    #[test]
+    fn test_preview_choices() {
+        let active = HashSet::from([6, 8]);
+        let inactive = HashSet::new();
+        let choices = HashSet::from([(7, None), (2, None)]);
+        let previews = preview_choices(&choices, &active, &inactive);
+        assert_eq!(previews[0].choice, (2, None));
+        assert_eq!(previews[1].choice, (7, None));
+        let safest = &previews[1].odds;
+        assert_approx_eq!(safest.croak_chance, 104.0 / TOTAL_ROLLS);
+        // Each active column is offered whenever a pair of dice sum to it.
+        for (col, &chance) in safest.hit_chances.iter().enumerate() {
+            let rolls = all_rolls()
+                .filter(|&dice| pair_sums(dice).contains(&(col + 2)))
+                .count();
+            match col + 2 {
+                6..=8 => {
+                    assert_approx_eq!(chance, rolls as f64 / TOTAL_ROLLS);
+                }
+                _ => {
+                    assert_approx_eq!(chance, 0.0);
+                }
+            }
+        }
+        assert!(previews[0].odds.croak_chance > safest.croak_chance);
+    }
 }
@@ -0,0 +1,590 @@
+use anyhow::{anyhow, Context as _};
+use serde_json::{Map, Number, Value};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write as _},
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+use super::{
+    columns::HEIGHTS,
+    logic::evaluate_moves,
+    personality::Personality,
+    player::{Player, PlayerMode, RunOutcome},
+    record::{
+        choice_notation, sorted, GameRecord, RecordedRun, RecordedTurn, Ruleset, RECORD_FORMAT,
+        RECORD_VERSION,
+    },
+    saves::now,
+    GameState, SettingsState,
+};
+
+/// Name given to a game whose notation has no Game tag.
+const UNNAMED_GAME: &str = "Untitled game";
+/// Tags a game's notation can have, in the order they're written.
+/// A Custom bot's personality is written in full in its Personality tag, so it plays
+/// the same when read back. Only Custom players have one, and they can't do without it.
+const TAGS: [&str; 17] = [
+    "Game",
+    "Date",
+    "WinColumns",
+    "Player1",
+    "Mode1",
+    "Personality1",
+    "Player2",
+    "Mode2",
+    "Personality2",
+    "Player3",
+    "Mode3",
+    "Personality3",
+    "Player4",
+    "Mode4",
+    "Personality4",
+    "Seed",
+    "Result",
+];
+
+/// Where something is in a game's notation, counting from 1.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+/// A piece of a game's notation: a tag, or a word of the moves.
+#[derive(Debug)]
+enum Token {
+    Tag(String, String),
+    Word(String),
+}
+
+/// Error at a position in the notation.
+fn error_at(at: Position, message: impl Display) -> anyhow::Error {
+    anyhow!("line {}, column {}: {}", at.line, at.column, message)
+}
+
+/// Splits notation into tags and words, skipping whitespace and comments.
+struct Tokens<'a> {
+    chars: Peekable<Chars<'a>>,
+    at: Position,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            at: Position { line: 1, column: 1 },
+        }
+    }
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.at.line += 1;
+            self.at.column = 1;
+        } else {
+            self.at.column += 1;
+        }
+        Some(c)
+    }
+    /// Take the next character, which should be `expected`.
+    fn expect(&mut self, expected: char, what: &str) -> anyhow::Result<()> {
+        let at = self.at;
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(error_at(at, format!("expected {what}, found '{c}'"))),
+            None => Err(error_at(at, format!("expected {what}, found the end"))),
+        }
+    }
+    /// Take characters while they match.
+    fn take_while(&mut self, matches: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some(&c) = self.chars.peek().filter(|&&c| matches(c)) {
+            taken.push(c);
+            self.bump();
+        }
+        taken
+    }
+    /// A `[Name "value"]` tag, with `\"` and `\\` escaped in the value.
+    fn tag(&mut self) -> anyhow::Result<Token> {
+        self.expect('[', "'['")?;
+        let name = self.take_while(|c| c.is_ascii_alphanumeric());
+        if name.is_empty() {
+            return Err(error_at(self.at, "expected a tag name"));
+        }
+        self.take_while(|c| c == ' ');
+        self.expect('"', "'\"' to start the tag's value")?;
+        let mut value = String::new();
+        loop {
+            let at = self.at;
+            match self.bump() {
+                Some('"') => break,
+                Some('\\') => match self.bump() {
+                    Some(c @ ('"' | '\\')) => value.push(c),
+                    _ => return Err(error_at(at, "only \\\" and \\\\ can be escaped")),
+                },
+                Some('\n') | None => return Err(error_at(at, "tag value isn't closed with '\"'")),
+                Some(c) => value.push(c),
+            }
+        }
+        self.expect(']', "']' to close the tag")?;
+        Ok(Token::Tag(name, value))
+    }
+    /// The next token and where it starts, None at the end.
+    fn next_token(&mut self) -> anyhow::Result<Option<(Position, Token)>> {
+        loop {
+            self.take_while(char::is_whitespace);
+            let at = self.at;
+            match self.chars.peek() {
+                None => return Ok(None),
+                Some('{') => loop {
+                    match self.bump() {
+                        Some('}') => break,
+                        Some(_) => continue,
+                        None => return Err(error_at(at, "comment isn't closed with '}'")),
+                    }
+                },
+                Some('}') => return Err(error_at(at, "'}' without a comment to close")),
+                Some('[') => return Ok(Some((at, self.tag()?))),
+                Some(_) => {
+                    let word =
+                        self.take_while(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | '['));
+                    return Ok(Some((at, Token::Word(word))));
+                }
+            }
+        }
+    }
+}
+
+/// Player mode named in a Mode tag, with the player's Personality tag if they have one.
+/// A Custom bot's personality is read from its Personality tag, which no other mode has.
+fn parse_mode(
+    at: Position,
+    name: &str,
+    personality: Option<(Position, &str)>,
+) -> anyhow::Result<PlayerMode> {
+    let mode = match name {
+        "Human" => PlayerMode::Human,
+        "Safe" => PlayerMode::Safe,
+        "Normal" => PlayerMode::Normal,
+        "Risky" => PlayerMode::Risky,
+        "Custom" => {
+            let Some((at, personality)) = personality else {
+                return Err(error_at(at, "a Custom player needs a Personality tag"));
+            };
+            return Ok(PlayerMode::Custom(parse_personality(at, personality)?));
+        }
+        "Adaptive" => PlayerMode::Adaptive,
+        _ => return Err(error_at(at, format!("'{name}' isn't a player mode"))),
+    };
+    match personality {
+        Some((at, _)) => Err(error_at(
+            at,
+            format!("only a Custom player has a personality, not {name}"),
+        )),
+        None => Ok(mode),
+    }
+}
+
+/// A Custom bot's personality for its Personality tag, e.g. "risk_threshold=0.5 ...".
+fn personality_notation(personality: &Personality) -> String {
+    let Ok(Value::Object(parameters)) = serde_json::to_value(personality) else {
+        return String::new();
+    };
+    parameters
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A Custom bot's personality from its Personality tag, with every parameter given.
+fn parse_personality(at: Position, text: &str) -> anyhow::Result<Personality> {
+    let known = serde_json::to_value(Personality::default())?;
+    let mut parameters = Map::new();
+    for parameter in text.split_whitespace() {
+        let Some((name, value)) = parameter.split_once('=') else {
+            return Err(error_at(
+                at,
+                format!("expected a parameter like risk_threshold=0.5, found '{parameter}'"),
+            ));
+        };
+        if known.get(name).is_none() {
+            return Err(error_at(
+                at,
+                format!("'{name}' isn't a personality parameter"),
+            ));
+        }
+        let value = parse_number(at, value, "a number").and_then(|value: f64| {
+            Number::from_f64(value).ok_or_else(|| error_at(at, format!("{name} isn't finite")))
+        })?;
+        if parameters
+            .insert(name.to_string(), Value::Number(value))
+            .is_some()
+        {
+            return Err(error_at(at, format!("{name} is given twice")));
+        }
+    }
+    serde_json::from_value(Value::Object(parameters))
+        .map_err(|e| error_at(at, format!("the personality is incomplete, {e}")))
+}
+
+fn parse_number<T: FromStr>(at: Position, text: &str, what: &str) -> anyhow::Result<T> {
+    match text.parse() {
+        Ok(number) if !text.starts_with('+') => Ok(number),
+        _ => Err(error_at(at, format!("expected {what}, found '{text}'"))),
+    }
+}
+
+/// A roll, e.g. "3456:7+9" for taking 7 and 9, or "1166x" for croaking.
+fn parse_turn(at: Position, word: &str) -> anyhow::Result<RecordedTurn> {
+    let digits = word
+        .get(..4)
+        .filter(|dice| dice.chars().all(|c| ('1'..='6').contains(&c)));
+    let Some(digits) = digits else {
+        return Err(error_at(
+            at,
+            format!("expected a roll like 3456:7+9, found '{word}'"),
+        ));
+    };
+    let mut dice = [0; 4];
+    for (die, digit) in dice.iter_mut().zip(digits.chars()) {
+        *die = digit as usize - '0' as usize;
+    }
+    let chosen = match &word[4..] {
+        "x" => None,
+        choice => {
+            let Some(choice) = choice.strip_prefix(':') else {
+                return Err(error_at(
+                    at,
+                    format!("a roll should end with :columns or x, found '{word}'"),
+                ));
+            };
+            let mut columns = choice.split('+').map(|column| {
+                parse_number(at, column, "a column from 2 to 12").and_then(|column| {
+                    match (2..=12).contains(&column) {
+                        true => Ok(column),
+                        false => Err(error_at(at, format!("{column} isn't a column"))),
+                    }
+                })
+            });
+            let first = columns.next().expect("split always yields a column")?;
+            let second = columns.next().transpose()?;
+            if columns.next().is_some() {
+                return Err(error_at(at, "at most two columns can be taken"));
+            }
+            Some((first, second))
+        }
+    };
+    Ok(RecordedTurn {
+        dice,
+        options: vec![],
+        chosen,
+    })
+}
+
+impl GameRecord {
+    /// Write the game in Can't Hop notation. See the README for the format.
+    pub fn to_notation(&self) -> String {
+        let mut text = String::new();
+        let mut tag = |name: &str, value: &str| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(text, "[{name} \"{value}\"]");
+        };
+        tag("Game", &self.name);
+        tag("Date", &self.recorded.to_string());
+        tag("WinColumns", &self.ruleset.win_cols.to_string());
+        for (index, player) in self.players.iter().enumerate() {
+            tag(&format!("Player{}", index + 1), &player.name);
+            tag(&format!("Mode{}", index + 1), &player.mode.to_string());
+            if let PlayerMode::Custom(personality) = &player.mode {
+                let personality = personality_notation(personality);
+                tag(&format!("Personality{}", index + 1), &personality);
+            }
+        }
+        if let Some(seed) = self.seed {
+            tag("Seed", &seed.to_string());
+        }
+        match self.winner {
+            Some(winner) => tag("Result", &(winner + 1).to_string()),
+            None => tag("Result", "*"),
+        }
+        for (index, run) in self.runs.iter().enumerate() {
+            let _ = write!(text, "\n{}. P{}", index + 1, run.player + 1);
+            for turn in &run.turns {
+                let dice: String = turn.dice.iter().map(|die| die.to_string()).collect();
+                let _ = match turn.chosen {
+                    Some(choice) => write!(text, " {dice}:{}", choice_notation(choice)),
+                    None if turn.options.is_empty() => write!(text, " {dice}x"),
+                    // a roll still waiting for a choice can't be written, so is left out
+                    None => continue,
+                };
+            }
+            if run.outcome == RunOutcome::Banked {
+                text.push_str(" !");
+            }
+        }
+        text.push('\n');
+        text
+    }
+
+    /// Read a game from Can't Hop notation, and play it back to check it follows the rules.
+    /// Errors say where in the notation the problem is.
+    pub fn from_notation(text: &str) -> anyhow::Result<Self> {
+        let mut tokens = Tokens::new(text);
+        let mut tags: BTreeMap<String, (Position, String)> = BTreeMap::new();
+        let mut next = tokens.next_token()?;
+        while let Some((at, Token::Tag(name, value))) = &next {
+            if !TAGS.contains(&name.as_str()) {
+                return Err(error_at(*at, format!("'{name}' isn't a tag")));
+            }
+            if tags.insert(name.clone(), (*at, value.clone())).is_some() {
+                let at = *at;
+                return Err(error_at(at, format!("{name} is tagged twice")));
+            }
+            next = tokens.next_token()?;
+        }
+        let end = tokens.at;
+        let tag = |name: &str| tags.get(name).map(|(at, value)| (*at, value.as_str()));
+        let required =
+            |name: &str| tag(name).ok_or_else(|| error_at(end, format!("missing the {name} tag")));
+
+        let mut players = vec![];
+        for number in 1..=4 {
+            let Some((at, name)) = tag(&format!("Player{number}")) else {
+                for orphan in ["Mode", "Personality"] {
+                    if let Some((at, _)) = tag(&format!("{orphan}{number}")) {
+                        return Err(error_at(
+                            at,
+                            format!("{orphan}{number} without a Player{number}"),
+                        ));
+                    }
+                }
+                break;
+            };
+            let personality = tag(&format!("Personality{number}"));
+            let mode = match tag(&format!("Mode{number}")) {
+                Some((at, mode)) => parse_mode(at, mode, personality)?,
+                // untagged players are human
+                None => parse_mode(at, "Human", personality)?,
+            };
+            players.push(Player::new(number - 1, mode, name.to_string()));
+        }
+        if let Some((at, _)) = (players.len() + 2..=4).find_map(|n| tag(&format!("Player{n}"))) {
+            return Err(error_at(
+                at,
+                "players should be numbered from 1 without gaps",
+            ));
+        }
+        if players.len() < 2 {
+            return Err(error_at(end, "a game needs Player1 and Player2 tags"));
+        }
+        let (at, win_cols) = required("WinColumns")?;
+        let win_cols = parse_number(at, win_cols, "a number of columns")?;
+        let seed = match tag("Seed") {
+            Some((at, seed)) => Some(parse_number(at, seed, "a seed")?),
+            None => None,
+        };
+        let recorded = match tag("Date") {
+            Some((at, date)) => parse_number(at, date, "seconds since 1970")?,
+            None => now(),
+        };
+        let winner = match required("Result")? {
+            (_, "*") => None,
+            (at, number) => match parse_number(at, number, "a player number or *")? {
+                number if (1..=players.len()).contains(&number) => Some(number - 1),
+                number => return Err(error_at(at, format!("there's no player {number}"))),
+            },
+        };
+
+        let mut runs: Vec<RecordedRun> = vec![];
+        let mut finished = true;
+        while let Some((at, token)) = next {
+            let Token::Word(word) = token else {
+                return Err(error_at(at, "tags go before the runs"));
+            };
+            let number = runs.len() + 1;
+            if word != format!("{number}.") {
+                return Err(error_at(
+                    at,
+                    format!("expected run '{number}.', found '{word}'"),
+                ));
+            }
+            if !finished {
+                return Err(error_at(
+                    at,
+                    "the run before doesn't end with a bank (!) or croak",
+                ));
+            }
+            let Some((at, Token::Word(player))) = tokens.next_token()? else {
+                return Err(error_at(
+                    tokens.at,
+                    "expected the player taking the run, like P1",
+                ));
+            };
+            let player = match player.strip_prefix('P') {
+                Some(player) => parse_number(at, player, "a player number")?,
+                None => {
+                    return Err(error_at(
+                        at,
+                        format!("expected a player like P1, found '{player}'"),
+                    ))
+                }
+            };
+            if !(1..=players.len()).contains(&player) {
+                return Err(error_at(at, format!("there's no player {player}")));
+            }
+            let mut run = RecordedRun {
+                player: player - 1,
+                turns: vec![],
+                outcome: RunOutcome::InProgress,
+            };
+            next = tokens.next_token()?;
+            while let Some((at, Token::Word(word))) = &next {
+                if word.ends_with('.') {
+                    break;
+                }
+                if run.outcome != RunOutcome::InProgress {
+                    return Err(error_at(*at, "the run has already ended"));
+                }
+                if word == "!" {
+                    if run.turns.is_empty() {
+                        return Err(error_at(*at, "can't bank before rolling"));
+                    }
+                    run.outcome = RunOutcome::Banked;
+                } else {
+                    let turn = parse_turn(*at, word)?;
+                    if turn.chosen.is_none() {
+                        run.outcome = RunOutcome::Croaked;
+                    }
+                    run.turns.push(turn);
+                }
+                next = tokens.next_token()?;
+            }
+            finished = run.outcome != RunOutcome::InProgress;
+            runs.push(run);
+        }
+
+        let mut record = GameRecord {
+            format: RECORD_FORMAT.to_string(),
+            version: RECORD_VERSION,
+            name: tag("Game")
+                .map_or(UNNAMED_GAME, |(_, name)| name)
+                .to_string(),
+            recorded,
+            ruleset: Ruleset {
+                win_cols,
+                heights: HEIGHTS.to_vec(),
+            },
+            players,
+            seed,
+            runs,
+            winner,
+        };
+        record.fill_options();
+        record
+            .replay()
+            .context("the game doesn't follow the rules")?;
+        Ok(record)
+    }
+
+    /// Work out the options each roll offered, which notation leaves out,
+    /// by playing the runs through. Rules aren't checked, that's for `replay`.
+    fn fill_options(&mut self) {
+        let settings = SettingsState::new(self.players.clone(), usize::MAX);
+        let mut game = GameState::default();
+        game.new_game(settings);
+        for run in &mut self.runs {
+            game.current_player = run.player;
+            for turn in &mut run.turns {
+                let choices =
+                    evaluate_moves(turn.dice, &game.get_selected(), &game.get_unavailable());
+                turn.options = sorted(&choices);
+                if let Some((first, second)) = turn.chosen {
+                    // convert from 2d6 to index
+                    let _ = game.risk_columns(first - 2, second.map(|x| x - 2));
+                }
+            }
+            game.next_player(run.outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::Simulation;
+
+    #[test]
+    fn test_notation_round_trip() {
+        let custom = Personality {
+            weight_rival: 2.25,
+            ..Personality::RISKY
+        };
+        let players = [
+            PlayerMode::Normal,
+            PlayerMode::Risky,
+            PlayerMode::Custom(custom),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(id, mode)| Player::new(id, mode, format!("\"{mode}\" Bot")))
+        .collect();
+        let mut simulation = Simulation::new(SettingsState::new(players, 3), 11).unwrap();
+        simulation.play();
+        let record = GameRecord::new(
+            "Bots".into(),
+            &simulation.game,
+            &simulation.history,
+            Some(11),
+        );
+        let notation = record.to_notation();
+        assert!(notation.contains("[Player1 \"\\\"Normal\\\" Bot\"]"));
+        assert!(notation.contains("\n1. P1 "));
+
+        let commented = notation.replacen("\n1. P1 ", "\n{ a tense start }\n1. P1 ", 1);
+        let read = GameRecord::from_notation(&commented).unwrap();
+        assert_eq!(read.runs, record.runs);
+        assert_eq!(read.players[0].name, record.players[0].name);
+        assert_eq!((read.winner, read.seed), (record.winner, Some(11)));
+        assert!(matches!(read.players[2].mode, PlayerMode::Custom(read) if read == custom));
+
+        let error = |text: &str| format!("{:#}", GameRecord::from_notation(text).unwrap_err());
+        let tags = "[WinColumns \"3\"]\n[Player1 \"A\"]\n[Player2 \"B\"]\n[Result \"*\"]\n";
+        assert_eq!(
+            error(&format!("{tags}1. P1 3456:7+9 1234:3+7+4")),
+            "line 5, column 16: at most two columns can be taken"
+        );
+        assert_eq!(
+            error(&format!("{tags}1. P1 3456:7+9 !\n3. P2")),
+            "line 6, column 1: expected run '2.', found '3.'"
+        );
+        assert_eq!(
+            error(&format!("{tags}1. P1 3456:7+8 !")),
+            "the game doesn't follow the rules: run 1 (A): roll 1 [3, 4, 5, 6]: \
+             7+8 wasn't one of the options (7+11, 8+10, 9+9)"
+        );
+        assert_eq!(
+            error(&format!("{tags}1. P1 3456x")),
+            "the game doesn't follow the rules: run 1 (A): roll 1 [3, 4, 5, 6]: \
+             can't croak with options on offer (7+11, 8+10, 9+9)"
+        );
+        assert!(error("[Player1 \"A\"").starts_with("line 1, column 13: "));
+
+        // Custom bots need their personality in full, and no one else has one.
+        let custom = tags.replace("[Player2 \"B\"]", "[Player2 \"B\"]\n[Mode2 \"Custom\"]");
+        assert_eq!(
+            error(&custom),
+            "line 4, column 1: a Custom player needs a Personality tag"
+        );
+        let partial = custom.replace("[Result", "[Personality2 \"risk_threshold=0.5\"]\n[Result");
+        assert!(error(&partial).starts_with(
+            "line 5, column 1: the personality is incomplete, missing field `weight_active`"
+        ));
+        let human = tags.replace("[Result", "[Personality1 \"risk_threshold=0.5\"]\n[Result");
+        assert_eq!(
+            error(&human),
+            "line 4, column 1: only a Custom player has a personality, not Human"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::Store;
+
+use super::{
+    logic::calculate_croak_chance,
+    player::{PlayerRun, RunOutcome},
+    save, ColumnID,
+};
+
+/// What bots have learnt about how a human plays, from their recorded runs.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct OpponentModel {
+    /// Runs the player chose to end.
+    pub banked: usize,
+    /// Runs the player croaked.
+    pub croaked: usize,
+    /// Average croak chance (0.0 - 1.0) the player faced when they chose to stop.
+    /// Bots pull their own risk threshold towards it, to keep pace.
+    pub stop_chance: f64,
+    /// Times each column has been chosen, by column index.
+    pub column_picks: [usize; 11],
+}
+
+impl OpponentModel {
+    /// Learn from a finished run.
+    pub fn learn(&mut self, run: &PlayerRun) {
+        for turn in &run.turns {
+            match turn.chosen {
+                Some((first, Some(second))) => {
+                    self.column_picks[first] += 1;
+                    self.column_picks[second] += 1;
+                }
+                Some((first, None)) => self.column_picks[first] += 1,
+                None => (),
+            }
+        }
+        match run.outcome {
+            RunOutcome::InProgress => (),
+            RunOutcome::Croaked => self.croaked += 1,
+            RunOutcome::Banked => {
+                let Some(last) = run.turns.last() else {
+                    return; // stopped without hopping, nothing was risked
+                };
+                // The columns they were risking when they decided to stop.
+                let mut active_cols = last.active_cols.clone();
+                if let Some((first, second)) = last.chosen {
+                    // convert from index to 2d6
+                    active_cols.insert(first + 2);
+                    active_cols.extend(second.map(|x| x + 2));
+                }
+                let chance = calculate_croak_chance(&active_cols, &run.inactive_cols);
+                self.banked += 1;
+                self.stop_chance += (chance - self.stop_chance) / self.banked as f64;
+            }
+        }
+    }
+    /// How much the player favours a column (2d6), relative to an even spread over all 11.
+    /// 1.0 is average, 0.0 if they have never picked it.
+    pub fn preference(&self, col: ColumnID) -> f64 {
+        let total: usize = self.column_picks.iter().sum();
+        match self.column_picks.get(col.wrapping_sub(2)) {
+            Some(&picks) if total > 0 => {
+                picks as f64 * self.column_picks.len() as f64 / total as f64
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// Models of every human the bots have played, by profile id, or by name for players
+/// without a profile.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct OpponentModels(pub BTreeMap<String, OpponentModel>);
+
+impl OpponentModels {
+    /// Update opponent models from disk. If they can't be loaded, they're backed up
+    /// and the bots start learning afresh instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "opponents") {
+            Ok(models) => *self = models.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save opponent models to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "opponents", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::DiceResult;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_learn_from_banked_run() {
+        let mut run = PlayerRun::start(HashSet::new());
+        run.start_turn(DiceResult::default(), HashSet::new());
+        run.turn_mut().chosen = Some((4, Some(6))); // columns 6 & 8
+        run.start_turn(DiceResult::default(), HashSet::from([6, 8]));
+        run.turn_mut().chosen = Some((5, None)); // column 7
+        run.outcome = RunOutcome::Banked;
+
+        let mut model = OpponentModel::default();
+        model.learn(&run);
+        assert_eq!(model.banked, 1);
+        // stopped while risking 6, 7 & 8
+        let chance = calculate_croak_chance(&HashSet::from([6, 7, 8]), &HashSet::new());
+        assert_eq!(model.stop_chance, chance);
+        assert_eq!(model.preference(7), 11.0 / 3.0);
+        assert_eq!(model.preference(2), 0.0);
+    }
+}
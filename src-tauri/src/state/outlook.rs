@@ -0,0 +1,270 @@
+use anyhow::anyhow;
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use super::{
+    logic::{all_rolls, evaluate_moves},
+    Choice, ColumnID, GameState,
+};
+
+/// Most rolls ahead an outlook will look, as the cost grows with each one.
+pub const MAX_OUTLOOK_ROLLS: usize = 8;
+
+/// What to expect from the rest of a run if the player keeps rolling.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutlook {
+    /// Chance (0.0 - 1.0) of surviving each number of rolls: the first is 1 roll, then 2, etc.
+    pub survival: Vec<f64>,
+    /// Expected change in the hops risked on each column (by index) after rolling
+    /// every one of the rolls and then stopping. Negative when the risk outweighs the gains.
+    pub expected_gain: [f64; 11],
+    /// Progress banked by stopping now, in columns' worth of hops.
+    pub stop_value: f64,
+    /// Expected progress banked by rolling once more and then stopping, in columns' worth.
+    pub roll_value: f64,
+}
+
+/// A run in progress: which columns are active and how many hops are risked on each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct RunPosition {
+    /// Active columns, bit per column index.
+    pub active: u16,
+    /// Hops risked on each column (by index), never more than are left to the top.
+    pub risked: [u8; 11],
+}
+
+impl RunPosition {
+    /// Progress risked, in columns' worth of hops.
+    pub fn progress(&self, heights: &[usize; 11]) -> f64 {
+        self.risked
+            .iter()
+            .zip(heights)
+            .map(|(&risked, &height)| risked as f64 / height as f64)
+            .sum()
+    }
+    fn active_cols(&self) -> HashSet<ColumnID> {
+        (0..11)
+            .filter(|idx| self.active & (1 << idx) != 0)
+            .map(|idx| idx + 2)
+            .collect()
+    }
+}
+
+/// Follows runs through every roll of the dice, from one board position.
+pub(super) struct RunExplorer {
+    /// Hops left to the top of each column (by index) for the player.
+    pub remaining: [usize; 11],
+    /// Height of each column (by index).
+    pub heights: [usize; 11],
+    inactive_cols: HashSet<ColumnID>,
+    /// Each distinct roll with its chance, as the order of the dice doesn't matter.
+    rolls: Vec<([usize; 4], f64)>,
+    /// Moves offered by each distinct roll, by active columns.
+    moves: HashMap<u16, Arc<[Vec<Choice>]>>,
+    /// Survival chance and expected risked hops at the end, by position and rolls left.
+    memo: HashMap<(RunPosition, usize), (f64, [f64; 11])>,
+}
+
+impl RunExplorer {
+    pub fn new(game: &GameState) -> (Self, RunPosition) {
+        let player = game.current_player;
+        let mut remaining = [0; 11];
+        let mut heights = [0; 11];
+        let mut position = RunPosition {
+            active: 0,
+            risked: [0; 11],
+        };
+        for (idx, column) in game.columns.iter().enumerate() {
+            remaining[idx] = column.height.saturating_sub(column.hops[player]);
+            heights[idx] = column.height;
+            if column.risked > 0 {
+                position.active |= 1 << idx;
+            }
+            position.risked[idx] = column.risked.min(remaining[idx]) as u8;
+        }
+        let mut rolls: BTreeMap<[usize; 4], usize> = BTreeMap::new();
+        for mut dice in all_rolls() {
+            dice.sort_unstable();
+            *rolls.entry(dice).or_default() += 1;
+        }
+        let explorer = Self {
+            remaining,
+            heights,
+            inactive_cols: game.get_unavailable(),
+            rolls: rolls
+                .into_iter()
+                .map(|(dice, ways)| (dice, ways as f64 / 1296.0))
+                .collect(),
+            moves: HashMap::new(),
+            memo: HashMap::new(),
+        };
+        (explorer, position)
+    }
+    /// Moves offered by each distinct roll with the given columns active.
+    pub fn moves(&mut self, position: &RunPosition) -> Arc<[Vec<Choice>]> {
+        let Self {
+            moves,
+            rolls,
+            inactive_cols,
+            ..
+        } = self;
+        moves
+            .entry(position.active)
+            .or_insert_with(|| {
+                let active_cols = position.active_cols();
+                rolls
+                    .iter()
+                    .map(|(dice, _)| {
+                        let mut choices: Vec<Choice> =
+                            evaluate_moves(*dice, &active_cols, inactive_cols)
+                                .into_iter()
+                                .collect();
+                        choices.sort_unstable();
+                        choices
+                    })
+                    .collect()
+            })
+            .clone()
+    }
+    /// Chance of each distinct roll.
+    pub fn chances(&self) -> impl Iterator<Item = f64> + '_ {
+        self.rolls.iter().map(|(_, chance)| *chance)
+    }
+    /// Take a choice (2d6), risking a hop on each of its columns. Hops past the top are lost.
+    pub fn play(&self, position: &RunPosition, (first, second): Choice) -> RunPosition {
+        let mut next = *position;
+        for col in [Some(first), second].into_iter().flatten() {
+            let idx = col - 2;
+            next.active |= 1 << idx;
+            if (next.risked[idx] as usize) < self.remaining[idx] {
+                next.risked[idx] += 1;
+            }
+        }
+        next
+    }
+    /// The choice a player keeping on rolling takes: the most hops that count,
+    /// then the fewest new columns, then columns rolled most often.
+    fn greedy_choice(&self, position: &RunPosition, choices: &[Choice]) -> Choice {
+        let rank = |&(first, second): &Choice| {
+            let cols = [Some(first), second];
+            let cols = cols.iter().flatten();
+            let gained: u8 = self
+                .play(position, (first, second))
+                .risked
+                .iter()
+                .zip(position.risked)
+                .map(|(after, before)| after - before)
+                .sum();
+            let new = cols
+                .clone()
+                .filter(|&&col| position.active & (1 << (col - 2)) == 0)
+                .count();
+            let rarity: usize = cols.map(|&col| col.abs_diff(7)).sum();
+            (Reverse(gained), new, rarity)
+        };
+        // Choices are sorted, so ties go to the lowest.
+        *choices
+            .iter()
+            .min_by_key(|choice| rank(choice))
+            .expect("only called with choices")
+    }
+    /// Chance of surviving `rolls` more rolls, always taking the greedy choice,
+    /// and the expected hops risked on each column at the end (0 if croaked).
+    fn explore(&mut self, position: RunPosition, rolls: usize) -> (f64, [f64; 11]) {
+        if rolls == 0 {
+            return (1.0, position.risked.map(|risked| risked as f64));
+        }
+        if let Some(&result) = self.memo.get(&(position, rolls)) {
+            return result;
+        }
+        let next: Vec<(f64, Option<Choice>)> = {
+            let moves = self.moves(&position);
+            self.chances()
+                .zip(moves.iter())
+                .map(|(chance, choices)| {
+                    let choice =
+                        (!choices.is_empty()).then(|| self.greedy_choice(&position, choices));
+                    (chance, choice)
+                })
+                .collect()
+        };
+        let mut survival = 0.0;
+        let mut risked = [0.0; 11];
+        for (chance, choice) in next {
+            let Some(choice) = choice else {
+                continue; // croaked, losing everything risked
+            };
+            let (survived, expected) = self.explore(self.play(&position, choice), rolls - 1);
+            survival += chance * survived;
+            for (total, hops) in risked.iter_mut().zip(expected) {
+                *total += chance * hops;
+            }
+        }
+        self.memo.insert((position, rolls), (survival, risked));
+        (survival, risked)
+    }
+}
+
+impl GameState {
+    /// What the current player can expect from rolling up to `rolls` more times.
+    pub fn run_outlook(&self, rolls: usize) -> anyhow::Result<RunOutlook> {
+        if !(1..=MAX_OUTLOOK_ROLLS).contains(&rolls) {
+            return Err(anyhow!(
+                "Can only look 1 to {} rolls ahead, not {}",
+                MAX_OUTLOOK_ROLLS,
+                rolls
+            ));
+        }
+        let (mut explorer, position) = RunExplorer::new(self);
+        let survival = (1..=rolls)
+            .map(|rolls| explorer.explore(position, rolls).0)
+            .collect();
+        let (_, expected) = explorer.explore(position, rolls);
+        let mut expected_gain = [0.0; 11];
+        for (idx, gain) in expected_gain.iter_mut().enumerate() {
+            *gain = expected[idx] - position.risked[idx] as f64;
+        }
+        let (_, after_roll) = explorer.explore(position, 1);
+        let roll_value = after_roll
+            .iter()
+            .zip(explorer.heights)
+            .map(|(hops, height)| hops / height as f64)
+            .sum();
+        Ok(RunOutlook {
+            survival,
+            expected_gain,
+            stop_value: position.progress(&explorer.heights),
+            roll_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::state::{calculate_croak_chance, human_game};
+
+    #[test]
+    fn test_outlook_survival() {
+        let mut game = human_game(2, 3);
+        // risking 6, 7 & 8, one hop from the top of 7
+        game.risk_columns(4, Some(6)).unwrap();
+        game.columns[5].hops[0] = 12;
+        game.risk_columns(5, None).unwrap();
+        let outlook = game.run_outlook(3).unwrap();
+
+        let croak = calculate_croak_chance(&game.get_selected(), &game.get_unavailable());
+        // With all three columns chosen, every roll has the same chance of croaking.
+        for (rolls, survival) in outlook.survival.iter().enumerate() {
+            let expected = (1.0 - croak).powi(rolls as i32 + 1);
+            assert!((survival - expected).abs() < 1e-9);
+        }
+        // 7 has topped out, so it can't gain, only be lost.
+        assert!(outlook.expected_gain[5] < 0.0);
+        assert!((outlook.stop_value - (1.0 / 11.0 + 1.0 / 13.0 + 1.0 / 11.0)).abs() < 1e-9);
+        assert!(game.run_outlook(0).is_err());
+    }
+}
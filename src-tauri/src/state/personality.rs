@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::Store;
+
+use super::save;
+
+/// Tunable parameters that drive how a bot player hops, stops and picks columns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Personality {
+    /// Bonus for using an already active column if < 3 are active
+    pub weight_active: f64,
+    /// Reward for progress already made
+    pub weight_progress: f64,
+    /// Reward for being closer to the top
+    pub weight_distance: f64,
+    /// Reward for blocking opponents who are close
+    pub weight_opponent: f64,
+    /// Reward for racing opponents on the columns they're known to favour
+    #[serde(default = "default_weight_rival")]
+    pub weight_rival: f64,
+    /// Reward for columns statistically easier to roll
+    pub weight_probability: f64,
+    /// Croak chance (0.0 - 1.0) the bot is willing to face at the start of a run
+    pub risk_threshold: f64,
+    /// How much the threshold decreases per hop made in the current run
+    pub risk_aversion_per_hop: f64,
+    /// Lowest the threshold can drop to, however long the run
+    pub minimum_risk_threshold: f64,
+}
+
+/// Rivals were weighted like opponents before they had their own weight, which was
+/// the same for every built in bot.
+fn default_weight_rival() -> f64 {
+    Personality::NORMAL.weight_rival
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+impl Personality {
+    /// Safe bot avoids risks. Stops if bust chance is relatively low.
+    pub const SAFE: Self = Self::with_threshold(0.35);
+    /// Normal bot takes calculated risks. Standard threshold.
+    pub const NORMAL: Self = Self::with_threshold(0.50);
+    /// Risky bot pushes their luck. High tolerance for busting.
+    pub const RISKY: Self = Self::with_threshold(0.65);
+
+    /// The standard column weights with the given starting risk threshold.
+    const fn with_threshold(risk_threshold: f64) -> Self {
+        Self {
+            weight_active: 5.0,
+            weight_progress: 1.0,
+            weight_distance: 2.0,
+            weight_opponent: 1.5,
+            weight_rival: 1.5,
+            weight_probability: 0.5,
+            risk_threshold,
+            risk_aversion_per_hop: 0.05,
+            minimum_risk_threshold: 0.05,
+        }
+    }
+    /// Risk threshold after making `hops` hops this run.
+    pub fn adjusted_risk_threshold(&self, hops: usize) -> f64 {
+        (self.risk_threshold - (hops as f64 * self.risk_aversion_per_hop))
+            .max(self.minimum_risk_threshold)
+    }
+}
+
+/// User created personalities, saved by name.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Personalities(pub BTreeMap<String, Personality>);
+
+impl Personalities {
+    /// Name a personality is saved under, if it has been saved.
+    pub fn name_of(&self, personality: &Personality) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(_, saved)| *saved == personality)
+            .map(|(name, _)| name.as_str())
+    }
+    /// Update saved personalities from disk. If they can't be loaded, they're backed up
+    /// and there are none instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "personalities") {
+            Ok(personalities) => *self = personalities.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save saved personalities to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "personalities", self)
+    }
+}
@@ -1,4 +1,7 @@
-use super::{logic::calculate_croak_chance, Choice, ColumnID, DiceResult, PlayerID};
+use super::{
+    adaptive::DEFAULT_SKILL, logic::calculate_croak_chance, personality::Personality,
+    profile::ProfileID, Choice, ColumnID, DiceResult, PlayerID,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashSet,
@@ -22,6 +25,42 @@ pub enum PlayerMode {
     /// This player will play risky and risk columns
     /// This player will risk columns based on the current game state
     Risky,
+    /// A user tuned AI player
+    /// This player will hop, stop and choose columns using its own personality
+    Custom(Personality),
+    /// An AI player that adapts to the humans
+    /// This player gets stronger as the humans win more games,
+    /// and plays harder when behind to keep the game close
+    Adaptive,
+}
+
+impl PlayerMode {
+    /// The personality driving this bot, or None for a human player.
+    pub fn personality(&self) -> Option<Personality> {
+        match self {
+            PlayerMode::Human => None,
+            PlayerMode::Safe => Some(Personality::SAFE),
+            PlayerMode::Normal => Some(Personality::NORMAL),
+            PlayerMode::Risky => Some(Personality::RISKY),
+            PlayerMode::Custom(personality) => Some(*personality),
+            // tuned from here as the game goes on, see `GameState::adaptive_strength`
+            PlayerMode::Adaptive => Some(Personality::adaptive(DEFAULT_SKILL)),
+        }
+    }
+}
+
+impl Display for PlayerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            PlayerMode::Human => "Human",
+            PlayerMode::Safe => "Safe",
+            PlayerMode::Normal => "Normal",
+            PlayerMode::Risky => "Risky",
+            PlayerMode::Custom(_) => "Custom",
+            PlayerMode::Adaptive => "Adaptive",
+        };
+        write!(f, "{mode}")
+    }
 }
 
 /// A player in the game
@@ -29,7 +68,7 @@ pub enum PlayerMode {
 pub struct Player {
     /// The player's mode
     /// This is used to determine how the player will play
-    /// The player can be a human, a safe AI, a normal AI, or a risky AI
+    /// The player can be a human, a safe AI, a normal AI, a risky AI, a custom AI or an adaptive AI
     pub mode: PlayerMode,
     /// The player's ID
     pub id: PlayerID,
@@ -39,6 +78,26 @@ pub struct Player {
     /// The number of columns the player has won
     /// This is used to determine if the player has won
     pub won_cols: Vec<ColumnID>,
+    /// Id of the profile the player is playing as, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileID>,
+}
+
+impl Player {
+    /// A player who hasn't won any columns, and isn't playing as a profile.
+    pub fn new(id: PlayerID, mode: PlayerMode, name: String) -> Self {
+        Self {
+            mode,
+            id,
+            name,
+            won_cols: vec![],
+            profile: None,
+        }
+    }
+    /// Key to remember this player by across games: their profile, or else their name.
+    pub fn key(&self) -> &str {
+        self.profile.as_deref().unwrap_or(&self.name)
+    }
 }
 
 /// Represents the outcome of a player's run.
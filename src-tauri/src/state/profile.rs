@@ -0,0 +1,173 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::Store;
+
+use super::{
+    player::{Player, PlayerMode},
+    save,
+    saves::now,
+};
+
+/// Stable id of a player profile, a random (version 4) UUID.
+pub type ProfileID = String;
+
+/// Someone who plays regularly, kept across games.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: ProfileID,
+    pub name: String,
+    /// Colour to show the player in, as a CSS colour.
+    #[serde(default)]
+    pub colour: Option<String>,
+    /// Avatar to show for the player, an emoji or image name.
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// Mode the player usually plays as, picked by default when they join a game.
+    #[serde(default)]
+    pub preferred_mode: PlayerMode,
+    /// When the profile was created, in seconds since the Unix epoch.
+    pub created: u64,
+}
+
+/// Details of a profile that can be chosen when creating or editing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileDetails {
+    pub name: String,
+    #[serde(default)]
+    pub colour: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub preferred_mode: PlayerMode,
+}
+
+/// Every player profile, by id.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profiles(pub BTreeMap<ProfileID, Profile>);
+
+/// A random (version 4) UUID.
+fn new_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+impl ProfileDetails {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("A profile needs a name"));
+        }
+        Ok(())
+    }
+}
+
+impl Profiles {
+    /// Create a profile with a new id.
+    pub fn create(&mut self, details: ProfileDetails) -> anyhow::Result<Profile> {
+        details.validate()?;
+        let profile = Profile {
+            id: new_uuid(),
+            name: details.name,
+            colour: details.colour,
+            avatar: details.avatar,
+            preferred_mode: details.preferred_mode,
+            created: now(),
+        };
+        self.0.insert(profile.id.clone(), profile.clone());
+        Ok(profile)
+    }
+    /// Change a profile's details, keeping its id and creation date.
+    pub fn update(&mut self, id: &str, details: ProfileDetails) -> anyhow::Result<Profile> {
+        details.validate()?;
+        let profile = self.get(id)?;
+        profile.name = details.name;
+        profile.colour = details.colour;
+        profile.avatar = details.avatar;
+        profile.preferred_mode = details.preferred_mode;
+        Ok(profile.clone())
+    }
+    /// Delete a profile. Games already played as it keep its id.
+    pub fn delete(&mut self, id: &str) -> anyhow::Result<Profile> {
+        self.0
+            .remove(id)
+            .ok_or_else(|| anyhow!("No profile with id {}", id))
+    }
+    fn get(&mut self, id: &str) -> anyhow::Result<&mut Profile> {
+        self.0
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("No profile with id {}", id))
+    }
+    /// Check the profiles players are playing as exist, and show them by their profile's name.
+    pub fn link(&self, players: &mut [Player]) -> anyhow::Result<()> {
+        for player in players {
+            let Some(id) = &player.profile else {
+                continue;
+            };
+            let profile = self
+                .0
+                .get(id)
+                .ok_or_else(|| anyhow!("No profile with id {}", id))?;
+            player.name = profile.name.clone();
+        }
+        Ok(())
+    }
+    /// Update profiles from disk. If they can't be loaded, they're backed up
+    /// and there are no profiles instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "profiles") {
+            Ok(profiles) => *self = profiles.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save profiles to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "profiles", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_profile_ids_are_stable() {
+        let mut profiles = Profiles::default();
+        let details = |name: &str| ProfileDetails {
+            name: name.into(),
+            colour: Some("#f87171".into()),
+            avatar: None,
+            preferred_mode: PlayerMode::Human,
+        };
+        let grandma = profiles.create(details("Grandma")).unwrap();
+        assert_eq!(grandma.id.len(), 36);
+        assert_eq!(&grandma.id[14..15], "4");
+        assert!(profiles.create(details(" ")).is_err());
+
+        let renamed = profiles.update(&grandma.id, details("Nana")).unwrap();
+        assert_eq!(renamed.id, grandma.id);
+        assert_eq!(renamed.created, grandma.created);
+
+        // players are remembered by their profile once linked to one
+        let mut game = crate::state::human_game(2, 3);
+        let player = &mut game.settings.players[0];
+        assert_eq!(player.key(), "Player 1");
+        player.profile = Some(grandma.id.clone());
+        assert_eq!(player.key(), grandma.id);
+        profiles.delete(&grandma.id).unwrap();
+        assert!(profiles.update(&grandma.id, details("Nana")).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::Store;
+
+use super::{
+    personality::Personalities,
+    player::{Player, PlayerMode},
+    save,
+    saves::now,
+    GameState,
+};
+
+/// Rating everyone starts from.
+pub const INITIAL_RATING: f64 = 1500.0;
+/// Most a rating can move in a two player game. Split between the opponents in bigger games,
+/// so a game counts the same however many play it.
+const K_FACTOR: f64 = 32.0;
+/// Bots that can be recommended as opponents.
+const BOT_MODES: [PlayerMode; 4] = [
+    PlayerMode::Safe,
+    PlayerMode::Normal,
+    PlayerMode::Risky,
+    PlayerMode::Adaptive,
+];
+
+/// A player's (Elo) rating after a game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingChange {
+    /// When the game finished, in seconds since the Unix epoch.
+    pub finished: u64,
+    pub rating: f64,
+}
+
+/// A player's or bot's rating, and how it got there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rating {
+    /// Name the player last played under, or the bot's mode.
+    pub name: String,
+    pub rating: f64,
+    /// Rated games played.
+    pub games: usize,
+    /// Rating after each game, oldest first.
+    pub history: Vec<RatingChange>,
+}
+
+/// Ratings of every player profile, human guest and kind of bot.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Ratings(pub BTreeMap<String, Rating>);
+
+/// A place on the leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    /// Profile id or name of the player, or key of the bot.
+    pub key: String,
+    pub name: String,
+    pub rating: f64,
+    pub games: usize,
+    pub bot: bool,
+}
+
+/// How evenly matched a bot would be against a player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotMatch {
+    pub mode: PlayerMode,
+    pub rating: f64,
+    /// Player's expected score against the bot (0.0 - 1.0), 0.5 for an even game.
+    pub expected: f64,
+}
+
+/// Key a player is rated under, and the name they're rated as. Bots of the same mode share
+/// a rating whatever their name, and custom bots share one with the rest of their personality.
+fn rating_key(player: &Player, personalities: &Personalities) -> (String, String) {
+    match &player.mode {
+        PlayerMode::Human => (player.key().to_string(), player.name.clone()),
+        PlayerMode::Custom(personality) => {
+            // a personality that hasn't been saved goes by the bot's name
+            let name = personalities.name_of(personality).unwrap_or(&player.name);
+            (format!("bot:Custom:{name}"), format!("{name} Bot"))
+        }
+        mode => (format!("bot:{mode}"), format!("{mode} Bot")),
+    }
+}
+
+/// Expected score of a player rated `rating` against one rated `opponent`.
+fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+impl Ratings {
+    /// A rating, or the initial rating if they haven't played a rated game.
+    pub fn rating(&self, key: &str) -> f64 {
+        self.0
+            .get(key)
+            .map_or(INITIAL_RATING, |rating| rating.rating)
+    }
+    /// Update everyone's rating after a finished game. Each pair of players counts as
+    /// a game between them: the winner beats everyone, and the rest are placed by columns won.
+    /// Players sharing a rating, like two bots of the same mode, don't play each other,
+    /// and the game counts once towards their rating.
+    pub fn record_game(&mut self, state: &GameState, personalities: &Personalities) {
+        let Some(winner) = &state.winner else {
+            return;
+        };
+        let players = &state.settings.players;
+        let place = |player: &Player| match player.id == winner.id {
+            true => usize::MAX,
+            false => player.won_cols.len(),
+        };
+        let keys: Vec<(String, String)> = players
+            .iter()
+            .map(|player| rating_key(player, personalities))
+            .collect();
+        let before: Vec<f64> = keys.iter().map(|(key, _)| self.rating(key)).collect();
+        let k = K_FACTOR / (players.len() - 1).max(1) as f64;
+        // change and name of each rating
+        let mut changes: BTreeMap<&str, (f64, &str)> = BTreeMap::new();
+        for (i, player) in players.iter().enumerate() {
+            let change: f64 = players
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| keys[*j].0 != keys[i].0)
+                .map(|(j, opponent)| {
+                    let score = match place(player).cmp(&place(opponent)) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    k * (score - expected_score(before[i], before[j]))
+                })
+                .sum();
+            let (key, name) = &keys[i];
+            changes.entry(key).or_insert((0.0, name)).0 += change;
+        }
+        let finished = now();
+        for (key, (change, name)) in changes {
+            let rating = self.0.entry(key.to_string()).or_insert_with(|| Rating {
+                name: String::new(),
+                rating: INITIAL_RATING,
+                games: 0,
+                history: vec![],
+            });
+            rating.name = name.to_string();
+            rating.rating += change;
+            rating.games += 1;
+            rating.history.push(RatingChange {
+                finished,
+                rating: rating.rating,
+            });
+        }
+    }
+    /// Everyone who has played a rated game, highest rated first.
+    pub fn leaderboard(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .0
+            .iter()
+            .map(|(key, rating)| Standing {
+                key: key.clone(),
+                name: rating.name.clone(),
+                rating: rating.rating,
+                games: rating.games,
+                bot: key.starts_with("bot:"),
+            })
+            .collect();
+        standings.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        standings
+    }
+    /// Bots to play against the player with this profile id or name, fairest first.
+    pub fn recommend_bots(&self, key: &str) -> Vec<BotMatch> {
+        let rating = self.rating(key);
+        let mut matches: Vec<BotMatch> = BOT_MODES
+            .iter()
+            .map(|&mode| {
+                let bot = self.rating(&format!("bot:{mode}"));
+                BotMatch {
+                    mode,
+                    rating: bot,
+                    expected: expected_score(rating, bot),
+                }
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            (a.expected - 0.5)
+                .abs()
+                .total_cmp(&(b.expected - 0.5).abs())
+        });
+        matches
+    }
+    /// Update ratings from disk. If they can't be loaded, they're backed up
+    /// and everyone starts from the initial rating instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "ratings") {
+            Ok(ratings) => *self = ratings.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Save ratings to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "ratings", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::Personality;
+
+    #[test]
+    fn test_ratings_follow_results() {
+        let mut state = GameState::default();
+        state.settings.players[1].mode = PlayerMode::Safe;
+        let mut third = state.settings.players[1].clone();
+        (third.id, third.name, third.mode) = (2, "Player 3".into(), PlayerMode::Risky);
+        state.settings.players.push(third);
+        state.settings.players[1].won_cols = vec![5];
+        state.winner = Some(state.settings.players[0].clone());
+
+        let mut ratings = Ratings::default();
+        ratings.record_game(&state, &Personalities::default());
+        let board = ratings.leaderboard();
+        let order: Vec<&str> = board.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(order, ["Player 1", "bot:Safe", "bot:Risky"]);
+        // pairwise updates between equal ratings are zero sum
+        let total: f64 = board.iter().map(|s| s.rating).sum();
+        assert!((total - 3.0 * INITIAL_RATING).abs() < 1e-9);
+
+        // the fairest bot is the one that's rated closest
+        let fairest = ratings.recommend_bots("Player 1");
+        assert!(matches!(fairest[0].mode, PlayerMode::Safe));
+        assert!(fairest[0].expected > 0.5);
+        assert_eq!(ratings.0["Player 1"].history.len(), 1);
+
+        // bots sharing a rating count the game once, and custom bots go by their personality
+        let mut custom = Personality::RISKY;
+        custom.weight_opponent = 3.0;
+        let personalities = Personalities([("Blocker".to_string(), custom)].into());
+        state.settings.players[1].mode = PlayerMode::Risky;
+        state
+            .settings
+            .players
+            .push(state.settings.players[2].clone());
+        state.settings.players[3].mode = PlayerMode::Custom(custom);
+        ratings.record_game(&state, &personalities);
+        assert_eq!(ratings.0["bot:Risky"].games, 2);
+        assert_eq!(ratings.0["bot:Risky"].history.len(), 2);
+        assert_eq!(ratings.0["bot:Custom:Blocker"].name, "Blocker Bot");
+        let total: f64 = ratings.0.values().map(|r| r.rating).sum();
+        assert!((total - 4.0 * INITIAL_RATING).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::{anyhow, Context as _};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use super::{
+    columns::HEIGHTS,
+    logic::evaluate_moves,
+    player::{Player, RunOutcome},
+    saves::now,
+    stats::History,
+    Choice, DiceResult, GameState, PlayerID, SettingsState,
+};
+
+/// Identifies a game record file.
+pub const RECORD_FORMAT: &str = "cant-hop-game";
+/// Version of the game record layout. Bump it whenever a record can't be read as it was.
+pub const RECORD_VERSION: u64 = 1;
+
+/// Rules the game was played under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ruleset {
+    /// Columns a player has to claim to win.
+    pub win_cols: usize,
+    /// Hops from the bottom to the top of each column, from 2 to 12.
+    pub heights: Vec<usize>,
+}
+
+/// A roll, and what the player chose to do with it.
+/// Columns are numbered by their dice total, 2 - 12.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedTurn {
+    pub dice: [usize; 4],
+    /// Choices the roll offered, in order.
+    pub options: Vec<Choice>,
+    /// Choice taken, None if the roll croaked the run.
+    pub chosen: Option<Choice>,
+}
+
+/// One player's run, from their first roll until they banked or croaked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRun {
+    /// Index of the player taking the run.
+    pub player: PlayerID,
+    pub turns: Vec<RecordedTurn>,
+    pub outcome: RunOutcome,
+}
+
+/// Everything needed to play a game back exactly, to share it or attach it to a bug report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Always `RECORD_FORMAT`.
+    pub format: String,
+    pub version: u64,
+    pub name: String,
+    /// When the record was made, in seconds since the Unix epoch.
+    pub recorded: u64,
+    pub ruleset: Ruleset,
+    pub players: Vec<Player>,
+    /// Seed the dice were thrown with, for simulated games. Games played in the app
+    /// aren't seeded, but every roll is recorded, so none is needed to play them back.
+    pub seed: Option<u64>,
+    /// Every run in the order they were played.
+    pub runs: Vec<RecordedRun>,
+    /// Index of the player who won, None if the game wasn't finished.
+    pub winner: Option<PlayerID>,
+}
+
+impl GameRecord {
+    /// Record a game from its final state and history.
+    pub fn new(name: String, state: &GameState, history: &History, seed: Option<u64>) -> Self {
+        let players = state
+            .settings
+            .players
+            .iter()
+            .map(|player| Player {
+                won_cols: vec![],
+                ..player.clone()
+            })
+            .collect();
+        let runs = history
+            .runs_in_order()
+            .into_iter()
+            // skip the next run, waiting for its first roll
+            .filter(|(_, run)| !(run.turns.is_empty() && run.outcome == RunOutcome::InProgress))
+            .map(|(player, run)| RecordedRun {
+                player,
+                turns: run
+                    .turns
+                    .iter()
+                    .map(|turn| RecordedTurn {
+                        dice: turn.options.dice,
+                        options: sorted(&turn.options.choices),
+                        // convert from index to 2d6
+                        chosen: turn
+                            .chosen
+                            .map(|(first, second)| (first + 2, second.map(|second| second + 2))),
+                    })
+                    .collect(),
+                outcome: run.outcome,
+            })
+            .collect();
+        let winner = state.winner.as_ref().and_then(|winner| {
+            let players = &state.settings.players;
+            players.iter().position(|player| player.id == winner.id)
+        });
+        Self {
+            format: RECORD_FORMAT.to_string(),
+            version: RECORD_VERSION,
+            name,
+            recorded: now(),
+            ruleset: Ruleset {
+                win_cols: state.settings.win_cols,
+                heights: state.columns.iter().map(|column| column.height).collect(),
+            },
+            players,
+            seed,
+            runs,
+            winner,
+        }
+    }
+
+    /// Play the record back through the game's rules, checking every roll offered the recorded
+    /// options and every choice was one of them. Returns the game as it was left.
+    pub fn replay(&self) -> anyhow::Result<(GameState, History)> {
+        if self.format != RECORD_FORMAT {
+            return Err(anyhow!("not a Can't Hop game record"));
+        }
+        if self.version > RECORD_VERSION {
+            return Err(anyhow!(
+                "recorded by a newer version of the game (record version {}, this reads up to {})",
+                self.version,
+                RECORD_VERSION
+            ));
+        }
+        if self.ruleset.heights != HEIGHTS {
+            return Err(anyhow!(
+                "only the standard board's column heights can be played"
+            ));
+        }
+        if !(1..=HEIGHTS.len()).contains(&self.ruleset.win_cols) {
+            return Err(anyhow!("can't play to {} columns", self.ruleset.win_cols));
+        }
+        let mut history = History::default();
+        history.new_game(self.players.len())?;
+        let mut game = GameState::default();
+        let settings = SettingsState::new(self.players.clone(), self.ruleset.win_cols);
+        game.new_game(settings);
+
+        for (index, run) in self.runs.iter().enumerate() {
+            let player = &self.players[game.current_player];
+            replay_run(&mut game, &mut history, run, index + 1 == self.runs.len())
+                .with_context(|| format!("run {} ({})", index + 1, player.name))?;
+        }
+        let winner = game.winner.as_ref().map(|_| game.current_player);
+        if winner != self.winner {
+            let name = |player: Option<PlayerID>| match player.and_then(|p| self.players.get(p)) {
+                Some(player) => player.name.clone(),
+                None => "nobody".to_string(),
+            };
+            return Err(anyhow!(
+                "the record says {} won, but playing it back {} did",
+                name(self.winner),
+                name(winner)
+            ));
+        }
+        Ok((game, history))
+    }
+}
+
+/// A choice (2d6) as it's written in a record's notation, e.g. "7+9".
+pub(super) fn choice_notation((first, second): Choice) -> String {
+    match second {
+        Some(second) => format!("{first}+{second}"),
+        None => format!("{first}"),
+    }
+}
+
+/// Describe a list of choices (2d6), e.g. "7+9, 8".
+fn describe(choices: &[Choice]) -> String {
+    let choices: Vec<String> = choices.iter().copied().map(choice_notation).collect();
+    choices.join(", ")
+}
+
+/// Choices in a fixed order, so records are the same however the choices were found.
+pub(super) fn sorted(choices: &HashSet<Choice>) -> Vec<Choice> {
+    choices
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Play back one run, checking it against the rules.
+fn replay_run(
+    game: &mut GameState,
+    history: &mut History,
+    run: &RecordedRun,
+    last: bool,
+) -> anyhow::Result<()> {
+    if game.winner.is_some() {
+        return Err(anyhow!("the game was already won"));
+    }
+    if run.player != game.current_player {
+        return Err(anyhow!(
+            "it was player {}'s turn, not player {}'s",
+            game.current_player + 1,
+            run.player + 1
+        ));
+    }
+    for (index, turn) in run.turns.iter().enumerate() {
+        let last = index + 1 == run.turns.len();
+        check_turn(game, history, turn, last, run.outcome)
+            .with_context(|| format!("roll {} {:?}", index + 1, turn.dice))?;
+    }
+    let croaked = run.turns.last().is_some_and(|turn| turn.chosen.is_none());
+    match run.outcome {
+        RunOutcome::InProgress if !last => Err(anyhow!("only the last run can be unfinished")),
+        RunOutcome::InProgress => Ok(()),
+        RunOutcome::Croaked if !croaked => Err(anyhow!("croaked without a roll to croak on")),
+        RunOutcome::Banked if croaked || run.turns.is_empty() => {
+            Err(anyhow!("banked without making a hop"))
+        }
+        outcome => {
+            game.next_player(outcome);
+            history.next_player(outcome, game.get_unavailable());
+            Ok(())
+        }
+    }
+}
+
+/// Play back one roll and choice, checking they follow the rules. `last` is whether it's
+/// the run's last roll, and `outcome` how the run ended.
+fn check_turn(
+    game: &mut GameState,
+    history: &mut History,
+    turn: &RecordedTurn,
+    last: bool,
+    outcome: RunOutcome,
+) -> anyhow::Result<()> {
+    if turn.dice.iter().any(|die| !(1..=6).contains(die)) {
+        return Err(anyhow!("dice should be 1 to 6"));
+    }
+    let selected = game.get_selected();
+    let choices = evaluate_moves(turn.dice, &selected, &game.get_unavailable());
+    let options = sorted(&choices);
+    if options != turn.options {
+        return Err(anyhow!(
+            "should offer ({}), but the record has ({})",
+            describe(&options),
+            describe(&turn.options)
+        ));
+    }
+    let result = DiceResult {
+        dice: turn.dice,
+        choices,
+        ..Default::default()
+    };
+    history.player_mut().record_roll(&result, &selected);
+    let Some(chosen) = turn.chosen else {
+        // Only a roll without options croaks. Otherwise the player can only have been
+        // left choosing, at the very end of a game that isn't finished.
+        return match (last, options.is_empty(), outcome) {
+            (false, _, _) => Err(anyhow!("rolled on after taking no choice")),
+            (true, true, _) | (true, false, RunOutcome::InProgress) => Ok(()),
+            (true, false, _) => Err(anyhow!(
+                "can't croak with options on offer ({})",
+                describe(&options)
+            )),
+        };
+    };
+    if !result.choices.contains(&chosen) {
+        return Err(anyhow!(
+            "{} wasn't one of the options ({})",
+            choice_notation(chosen),
+            describe(&options)
+        ));
+    }
+    // convert from 2d6 to index
+    let (first, second) = (chosen.0 - 2, chosen.1.map(|x| x - 2));
+    game.risk_columns(first, second)?;
+    history.player_mut().record_choice(first, second);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{PlayerMode, Simulation};
+
+    #[test]
+    fn test_records_replay_exactly() {
+        let players = [PlayerMode::Safe, PlayerMode::Risky]
+            .into_iter()
+            .enumerate()
+            .map(|(id, mode)| Player::new(id, mode, format!("{mode} Bot")))
+            .collect();
+        let mut simulation = Simulation::new(SettingsState::new(players, 3), 7).unwrap();
+        simulation.play();
+        let (game, history) = (&simulation.game, &simulation.history);
+        let record = GameRecord::new("Bots".into(), game, history, Some(7));
+        assert!(record.winner.is_some());
+
+        // a record read back from its file plays back to the same game
+        let file = serde_json::to_string(&record).unwrap();
+        let read: GameRecord = serde_json::from_str(&file).unwrap();
+        let (replayed, replayed_history) = read.replay().unwrap();
+        assert_eq!(format!("{:?}", replayed), format!("{:?}", game));
+        let again = GameRecord::new("Bots".into(), &replayed, &replayed_history, Some(7));
+        assert_eq!(again.runs, record.runs);
+
+        // tampering with a roll is caught
+        let mut tampered = record.clone();
+        tampered.runs[0].turns[0].dice = [6, 6, 6, 6];
+        let error = format!("{:#}", tampered.replay().unwrap_err());
+        assert!(
+            error.starts_with("run 1 (Safe Bot): roll 1 [6, 6, 6, 6]"),
+            "{error}"
+        );
+        // so is croaking a run on a roll that offered options
+        let mut tampered = record.clone();
+        let run = tampered
+            .runs
+            .iter_mut()
+            .find(|run| run.outcome == RunOutcome::Banked)
+            .unwrap();
+        run.turns.last_mut().unwrap().chosen = None;
+        run.outcome = RunOutcome::Croaked;
+        let error = format!("{:#}", tampered.replay().unwrap_err());
+        assert!(
+            error.contains("can't croak with options on offer"),
+            "{error}"
+        );
+        let mut tampered = record;
+        tampered.winner = Some(1 - tampered.winner.unwrap());
+        assert!(tampered.replay().is_err());
+    }
+}
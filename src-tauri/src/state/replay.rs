@@ -0,0 +1,261 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    player::{PlayerRun, RunOutcome},
+    stats::History,
+    Choice, GameState, PlayerID, SettingsState,
+};
+
+/// A point in a game: after `turn` rolls of run `run`, both counting from 0.
+/// Turn 0 is the start of the run, before its first roll.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ReplayPosition {
+    pub run: usize,
+    pub turn: usize,
+}
+
+/// The game as it was at a point in a replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub position: ReplayPosition,
+    /// Index of the player taking the run.
+    pub player: PlayerID,
+    /// Dice of the roll just made, None at the start of a run.
+    pub dice: Option<[usize; 4]>,
+    /// Columns (2 - 12) taken with the roll just made, None if it croaked.
+    pub chosen: Option<Choice>,
+    pub state: GameState,
+    /// Number of runs in the game, including the one waiting for its first roll.
+    pub runs: usize,
+    /// Where stepping back or forward goes, None at the start or end of the game.
+    pub previous: Option<ReplayPosition>,
+    pub next: Option<ReplayPosition>,
+}
+
+/// Plays a recorded game back to any run and turn, to step through it or carry on from there.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Settings the game started with.
+    settings: SettingsState,
+    /// Every run, with the index of the player taking it, in the order they were played.
+    runs: Vec<(PlayerID, PlayerRun)>,
+    win_timeline: Vec<Vec<f64>>,
+    position: ReplayPosition,
+}
+
+impl Replay {
+    /// Replay a game's history from its start, with the settings it started with.
+    pub fn new(settings: SettingsState, history: &History) -> anyhow::Result<Self> {
+        if settings.players.len() != history.players.len() {
+            return Err(anyhow!(
+                "the history has {} players, but the settings have {}",
+                history.players.len(),
+                settings.players.len()
+            ));
+        }
+        let runs: Vec<_> = history
+            .runs_in_order()
+            .into_iter()
+            .map(|(player, run)| (player, run.clone()))
+            .collect();
+        if runs.is_empty() {
+            return Err(anyhow!("the game hasn't started"));
+        }
+        Ok(Self {
+            settings,
+            runs,
+            win_timeline: history.win_timeline.clone(),
+            position: ReplayPosition::default(),
+        })
+    }
+    /// Number of runs, including the one waiting for its first roll.
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+    /// Number of rolls made in a run.
+    pub fn turns(&self, run: usize) -> usize {
+        self.runs.get(run).map_or(0, |(_, run)| run.turns.len())
+    }
+    pub fn position(&self) -> ReplayPosition {
+        self.position
+    }
+    /// The position one roll back, going back into the previous run from the start of a run.
+    pub fn previous(&self) -> Option<ReplayPosition> {
+        let ReplayPosition { run, turn } = self.position;
+        match (run, turn) {
+            (0, 0) => None,
+            (run, 0) => Some(ReplayPosition {
+                run: run - 1,
+                turn: self.turns(run - 1),
+            }),
+            (run, turn) => Some(ReplayPosition {
+                run,
+                turn: turn - 1,
+            }),
+        }
+    }
+    /// The position one roll on, going on to the next run from the end of a run.
+    pub fn next(&self) -> Option<ReplayPosition> {
+        let ReplayPosition { run, turn } = self.position;
+        if turn < self.turns(run) {
+            Some(ReplayPosition {
+                run,
+                turn: turn + 1,
+            })
+        } else if run + 1 < self.runs() {
+            Some(ReplayPosition {
+                run: run + 1,
+                turn: 0,
+            })
+        } else {
+            None
+        }
+    }
+    /// Step back a roll. Returns false at the start of the game.
+    pub fn back(&mut self) -> bool {
+        self.previous().map(|to| self.position = to).is_some()
+    }
+    /// Step forward a roll. Returns false at the end of the game.
+    pub fn forward(&mut self) -> bool {
+        self.next().map(|to| self.position = to).is_some()
+    }
+    /// Go to the start of a run.
+    pub fn jump_to_run(&mut self, run: usize) -> anyhow::Result<()> {
+        self.seek(ReplayPosition { run, turn: 0 })
+    }
+    /// Go to any run and turn.
+    pub fn seek(&mut self, position: ReplayPosition) -> anyhow::Result<()> {
+        if position.run >= self.runs() {
+            return Err(anyhow!(
+                "there's no run {}, the game has {}",
+                position.run + 1,
+                self.runs()
+            ));
+        }
+        if position.turn > self.turns(position.run) {
+            return Err(anyhow!(
+                "run {} only has {} rolls",
+                position.run + 1,
+                self.turns(position.run)
+            ));
+        }
+        self.position = position;
+        Ok(())
+    }
+    /// Rebuild the game and its history exactly as they were at the current position.
+    pub fn reconstruct(&self) -> anyhow::Result<(GameState, History)> {
+        let mut game = GameState::default();
+        game.new_game(self.settings.clone());
+        let mut history = History::default();
+        history.new_game(self.settings.players.len())?;
+        for (index, (player, run)) in self.runs.iter().enumerate() {
+            if *player != game.current_player && game.winner.is_none() {
+                return Err(anyhow!(
+                    "run {} should be player {}'s",
+                    index + 1,
+                    game.current_player + 1
+                ));
+            }
+            let turns = match index == self.position.run {
+                true => &run.turns[..self.position.turn],
+                false => &run.turns[..],
+            };
+            for turn in turns {
+                history
+                    .player_mut()
+                    .record_roll(&turn.options, &game.get_selected());
+                if let Some((first, second)) = turn.chosen {
+                    game.risk_columns(first, second)?;
+                    history.player_mut().record_choice(first, second);
+                }
+            }
+            if index == self.position.run {
+                break;
+            }
+            if run.outcome == RunOutcome::InProgress {
+                return Err(anyhow!("run {} was never finished", index + 1));
+            }
+            game.next_player(run.outcome);
+            history.next_player(run.outcome, game.get_unavailable());
+        }
+        // a chance at the start, then one after each run until the game was won
+        history.win_timeline = self.win_timeline.clone();
+        history.win_timeline.truncate(self.position.run + 1);
+        Ok((game, history))
+    }
+    /// The game at the current position, to show.
+    pub fn frame(&self) -> anyhow::Result<ReplayFrame> {
+        let (state, _) = self.reconstruct()?;
+        let ReplayPosition { run, turn } = self.position;
+        let (player, player_run) = &self.runs[run];
+        let roll = turn.checked_sub(1).map(|turn| &player_run.turns[turn]);
+        Ok(ReplayFrame {
+            position: self.position,
+            player: *player,
+            dice: roll.map(|roll| roll.options.dice),
+            // convert from index to 2d6
+            chosen: roll
+                .and_then(|roll| roll.chosen)
+                .map(|(first, second)| (first + 2, second.map(|second| second + 2))),
+            state,
+            runs: self.runs(),
+            previous: self.previous(),
+            next: self.next(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{Player, PlayerMode, Simulation};
+
+    #[test]
+    fn test_replay_reconstructs_positions() {
+        let players = [PlayerMode::Normal, PlayerMode::Risky]
+            .into_iter()
+            .enumerate()
+            .map(|(id, mode)| Player::new(id, mode, format!("{mode} Bot")))
+            .collect();
+        let settings = SettingsState::new(players, 3);
+        let mut simulation = Simulation::new(settings.clone(), 3).unwrap();
+        simulation.play();
+        let mut replay = Replay::new(settings, &simulation.history).unwrap();
+
+        // the start is a new game
+        let (start, _) = replay.reconstruct().unwrap();
+        let mut new_game = GameState::default();
+        new_game.new_game(simulation.game.settings.clone());
+        assert_eq!(format!("{start:?}"), format!("{new_game:?}"));
+        assert!(!replay.back());
+
+        // stepping all the way forward ends at the finished game
+        let mut steps = 0;
+        while replay.forward() {
+            steps += 1;
+        }
+        let (end, history) = replay.reconstruct().unwrap();
+        assert_eq!(format!("{end:?}"), format!("{:?}", simulation.game));
+        assert_eq!(history.runs_in_order().len(), replay.runs());
+        assert!(end.winner.is_some());
+        let rolls: usize = (0..replay.runs()).map(|run| replay.turns(run)).sum();
+        assert_eq!(steps, rolls + replay.runs() - 1);
+
+        // stepping back from a run's start lands after its previous run's last roll
+        replay.jump_to_run(1).unwrap();
+        assert!(replay.back());
+        assert_eq!(replay.position().turn, replay.turns(0));
+        let frame = replay.frame().unwrap();
+        assert_eq!(frame.player, 0);
+        assert_eq!(frame.next, Some(ReplayPosition { run: 1, turn: 0 }));
+        let hops = frame.state.columns.iter().map(|c| c.risked).sum::<usize>();
+        assert!(frame.chosen.is_none() || hops > 0);
+
+        let past_the_end = ReplayPosition {
+            run: replay.runs(),
+            turn: 0,
+        };
+        assert!(replay.seek(past_the_end).is_err());
+    }
+}
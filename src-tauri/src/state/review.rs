@@ -0,0 +1,222 @@
+use super::{
+    player::{PlayerRun, RunOutcome},
+    solver::{Objective, SolverCache},
+    Choice, GameState, History, Player, PlayerMode, SettingsState,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::Mutex};
+
+/// Number of worst decisions kept for each player.
+const TOP_BLUNDERS: usize = 3;
+/// Equity lost before a decision counts as a mistake, ignoring rounding.
+pub(super) const MISTAKE_EQUITY: f64 = 1e-6;
+
+/// Something a player can do during a run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    Hop,
+    Stop,
+    /// Take one of the offered choices (2d6).
+    Choose(Choice),
+}
+
+/// A decision that gave away equity, compared with the solver's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Blunder {
+    /// Run (from 0) the decision was made in.
+    pub run: usize,
+    /// Roll (from 0) within the run, or the number of rolls made for a final stop.
+    pub turn: usize,
+    pub played: Action,
+    pub best: Action,
+    /// Progress given away, in columns' worth of hops.
+    pub equity_lost: f64,
+}
+
+/// How well a player made their decisions over a game.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PlayerReview {
+    /// Decisions where there was a real choice to make.
+    pub decisions: usize,
+    /// Decisions that didn't match the solver.
+    pub mistakes: usize,
+    /// Progress given away in total, in columns' worth of hops.
+    pub equity_lost: f64,
+    /// Worst decisions, worst first.
+    pub blunders: Vec<Blunder>,
+}
+
+impl PlayerReview {
+    fn record(&mut self, blunder: Blunder) {
+        self.decisions += 1;
+        if blunder.equity_lost <= MISTAKE_EQUITY {
+            return;
+        }
+        self.mistakes += 1;
+        self.equity_lost += blunder.equity_lost;
+        self.blunders.push(blunder);
+        self.blunders
+            .sort_by(|a, b| b.equity_lost.total_cmp(&a.equity_lost));
+        self.blunders.truncate(TOP_BLUNDERS);
+    }
+}
+
+impl History {
+    /// Replay the game from the start, comparing each player's hop, stop and column
+    /// decisions with the solver playing for the most progress.
+    pub fn review(&self) -> Vec<PlayerReview> {
+        let count = self.players.len();
+        let mut reviews = vec![PlayerReview::default(); count];
+        if count == 0 {
+            return reviews;
+        }
+        let players = (0..count)
+            .map(|id| Player::new(id, PlayerMode::Human, format!("Player {}", id + 1)))
+            .collect();
+        let mut game = GameState::default();
+        // Nobody can win during the replay, so every run is played out.
+        game.new_game(SettingsState::new(players, usize::MAX));
+        let cache = Mutex::default();
+        for round in 0.. {
+            for (player, review) in reviews.iter_mut().enumerate() {
+                let Some(run) = self.players[player].runs().get(round) else {
+                    return reviews;
+                };
+                review_run(&mut game, run, round, &cache, review);
+                if run.outcome == RunOutcome::InProgress {
+                    return reviews;
+                }
+                game.next_player(run.outcome);
+            }
+        }
+        reviews
+    }
+}
+
+/// Review the decision to hop or stop before a roll.
+fn review_hop(
+    game: &GameState,
+    played: Action,
+    round: usize,
+    turn: usize,
+    cache: &Mutex<SolverCache>,
+    review: &mut PlayerReview,
+) {
+    if let Some((best, equity_lost)) = game.judge_hop(played, cache) {
+        review.record(Blunder {
+            run: round,
+            turn,
+            played,
+            best,
+            equity_lost,
+        });
+    }
+}
+
+impl GameState {
+    /// The solver's pick of hopping or stopping for the current player, and the progress
+    /// `played` gives away. None before anything is risked, as stopping then is pointless.
+    pub(super) fn judge_hop(
+        &self,
+        played: Action,
+        cache: &Mutex<SolverCache>,
+    ) -> Option<(Action, f64)> {
+        if self.get_selected().is_empty() {
+            return None;
+        }
+        let play = self.optimal_play(Objective::Progress, None, cache);
+        let (best, value) = match play.hop {
+            true => (Action::Hop, play.roll_value),
+            false => (Action::Stop, play.stop_value),
+        };
+        let played_value = match played {
+            Action::Hop => play.roll_value,
+            _ => play.stop_value,
+        };
+        Some((best, value - played_value))
+    }
+    /// The solver's pick of the offered `choices` (2d6), and the progress `chosen` gives away.
+    pub(super) fn judge_choice(
+        &self,
+        choices: &HashSet<Choice>,
+        chosen: Choice,
+        cache: &Mutex<SolverCache>,
+    ) -> (Choice, f64) {
+        let choices: Vec<Choice> = choices.iter().copied().collect();
+        let play = self.optimal_play(Objective::Progress, Some(&choices), cache);
+        let (best, value) = play.choices[0];
+        let played_value = play
+            .choices
+            .iter()
+            .find(|(choice, _)| *choice == chosen)
+            .map_or(value, |(_, value)| *value);
+        (best, value - played_value)
+    }
+}
+
+/// Review each decision in a run, playing it out on the game.
+fn review_run(
+    game: &mut GameState,
+    run: &PlayerRun,
+    round: usize,
+    cache: &Mutex<SolverCache>,
+    review: &mut PlayerReview,
+) {
+    for (turn, roll) in run.turns.iter().enumerate() {
+        review_hop(game, Action::Hop, round, turn, cache, review);
+        let Some((first, second)) = roll.chosen else {
+            break; // croaked
+        };
+        // convert from index to 2d6
+        let chosen = (first + 2, second.map(|x| x + 2));
+        if roll.options.choices.len() > 1 {
+            let (best, equity_lost) = game.judge_choice(&roll.options.choices, chosen, cache);
+            review.record(Blunder {
+                run: round,
+                turn,
+                played: Action::Choose(chosen),
+                best: Action::Choose(best),
+                equity_lost,
+            });
+        }
+        game.risk_columns(first, second)
+            .expect("columns chosen in the game exist");
+    }
+    if run.outcome == RunOutcome::Banked {
+        review_hop(game, Action::Stop, round, run.turns.len(), cache, review);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::DiceResult;
+
+    #[test]
+    fn test_review_flags_stopping_early() {
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        // Player 1 takes 6 & 8 at the start of their run, then stops.
+        let options = DiceResult {
+            dice: [2, 4, 3, 5],
+            choices: HashSet::from([(6, Some(8)), (7, Some(7))]),
+            ..Default::default()
+        };
+        history.player_mut().record_roll(&options, &HashSet::new());
+        history.player_mut().record_choice(4, Some(6));
+        history.next_player(RunOutcome::Banked, HashSet::new());
+
+        let reviews = history.review();
+        let review = &reviews[0];
+        // The choice, and stopping with only two hops risked.
+        assert_eq!(review.decisions, 2);
+        let stop = review
+            .blunders
+            .iter()
+            .find(|blunder| blunder.played == Action::Stop)
+            .expect("stopping so early is a mistake");
+        assert_eq!(stop.best, Action::Hop);
+        assert!(stop.equity_lost > 0.0);
+        assert_eq!(reviews[1].decisions, 0);
+    }
+}
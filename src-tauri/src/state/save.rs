@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Context as _};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tauri_plugin_store::Store;
+
+use super::saves::GAME_KEY_PREFIX;
+
+/// Version of the saved game format. Bump it, and add a migration from the previous
+/// version to `migrations`, whenever a change to the saved data can't be read as it was.
+pub const SAVE_VERSION: u64 = 1;
+
+/// Upgrades saved data by one version.
+type Migration = fn(Value) -> anyhow::Result<Value>;
+
+/// Migrations for the data saved under a key, the first from version 0 to 1, and so on.
+fn migrations(key: &str) -> &'static [Migration] {
+    match key {
+        "state" | "history" | "saves" | "profiles" | "archive" | "ratings" | "achievements"
+        | "personalities" | "adaptive" | "opponents" => &[unversioned],
+        key if key.starts_with(GAME_KEY_PREFIX) => &[unversioned],
+        _ => &[],
+    }
+}
+
+/// Upgrade the data saved under a key by one version, from version `from`.
+fn migrate(key: &str, from: u64, data: Value) -> anyhow::Result<Value> {
+    let migration = migrations(key)
+        .get(from as usize)
+        .with_context(|| format!("no way to upgrade '{key}' from save version {from}"))?;
+    let mut data = migration(data)?;
+    // Saved and archived games hold game states and histories, saved with the same version,
+    // so they're upgraded along with them.
+    let nested = |key| move |data| migrate(key, from, data);
+    match key {
+        "saves" => {
+            let games = data.get_mut("games").context("saved games are missing")?;
+            let games = games.as_array_mut().context("expected a list")?;
+            // only games saved before each had a key of its own are held in the list
+            for game in games.iter_mut().filter(|game| game.get("state").is_some()) {
+                migrate_game(game, from)?;
+            }
+        }
+        key if key.starts_with(GAME_KEY_PREFIX) => migrate_game(&mut data, from)?,
+        "archive" => migrate_each(&mut data, "history", nested("history"))?,
+        _ => {}
+    }
+    Ok(data)
+}
+
+/// Upgrade the game state and history of a saved game, saved with the same version.
+fn migrate_game(game: &mut Value, from: u64) -> anyhow::Result<()> {
+    for key in ["state", "history"] {
+        let value = game
+            .get_mut(key)
+            .with_context(|| format!("'{key}' is missing from a saved game"))?;
+        *value = migrate(key, from, value.take())
+            .with_context(|| format!("upgrading the '{key}' of a saved game"))?;
+    }
+    Ok(())
+}
+
+/// Upgrade a field of every object in a list.
+fn migrate_each(
+    list: &mut Value,
+    field: &str,
+    migrate: impl Fn(Value) -> anyhow::Result<Value>,
+) -> anyhow::Result<()> {
+    let list = list.as_array_mut().context("expected a list")?;
+    for (index, item) in list.iter_mut().enumerate() {
+        let value = item
+            .get_mut(field)
+            .with_context(|| format!("'{field}' is missing from number {}", index + 1))?;
+        *value = migrate(value.take())
+            .with_context(|| format!("upgrading the '{field}' of number {}", index + 1))?;
+    }
+    Ok(())
+}
+
+/// Version 0 was saved without a version, and reads the same as version 1.
+fn unversioned(data: Value) -> anyhow::Result<Value> {
+    Ok(data)
+}
+
+/// Store key for a backup of data that couldn't be loaded.
+fn backup_key(key: &str) -> String {
+    format!("{key}_backup")
+}
+
+/// Wrap data with the current save version.
+fn encode<T: Serialize>(data: &T) -> anyhow::Result<Value> {
+    Ok(json!({
+        "version": SAVE_VERSION,
+        "data": serde_json::to_value(data)?,
+    }))
+}
+
+/// Read saved data, migrating it up from the version it was saved with.
+fn decode<T: DeserializeOwned>(key: &str, saved: Value) -> anyhow::Result<T> {
+    let (version, mut data) = match saved {
+        Value::Object(mut saved) if saved.contains_key("version") => {
+            let version = saved
+                .get("version")
+                .and_then(Value::as_u64)
+                .context("save version isn't a number")?;
+            let data = saved.remove("data").context("save has no data")?;
+            (version, data)
+        }
+        unversioned => (0, unversioned),
+    };
+    if version > SAVE_VERSION {
+        return Err(anyhow!(
+            "saved by a newer version of the game (save version {}, this reads up to {})",
+            version,
+            SAVE_VERSION
+        ));
+    }
+    for from in version..SAVE_VERSION {
+        data = migrate(key, from, data)
+            .with_context(|| format!("upgrading from save version {from}"))?;
+    }
+    serde_json::from_value(data).context("saved data doesn't match this version of the game")
+}
+
+/// Save data under a key with the current save version.
+pub fn save<T: Serialize, R: tauri::Runtime>(
+    store: &Store<R>,
+    key: &str,
+    data: &T,
+) -> anyhow::Result<()> {
+    store.set(key, encode(data)?);
+    Ok(())
+}
+
+/// Load the data saved under a key, None if there isn't any.
+/// If it can't be loaded it's kept under the backup key, so it isn't lost when saving over it.
+pub fn load<T: DeserializeOwned, R: tauri::Runtime>(
+    store: &Store<R>,
+    key: &str,
+) -> anyhow::Result<Option<T>> {
+    let Some(saved) = store.get(key) else {
+        return Ok(None);
+    };
+    match decode(key, saved.clone()) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) => {
+            let backup = backup_key(key);
+            store.set(&backup, saved);
+            Err(e.context(format!(
+                "couldn't load the saved '{key}', so it has been kept as '{backup}' in the store"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{GameState, History, SaveSlots};
+
+    #[test]
+    fn test_decode_save_versions() {
+        let mut history = History::default();
+        history.new_game(3).unwrap();
+        // Saves from before versioning are the bare data.
+        let unversioned = serde_json::to_value(&history).unwrap();
+        let loaded: History = decode("history", unversioned).unwrap();
+        assert_eq!(loaded.players.len(), 3);
+
+        let saved = encode(&history).unwrap();
+        assert_eq!(saved["version"], SAVE_VERSION);
+        let loaded: History = decode("history", saved).unwrap();
+        assert_eq!(loaded.players.len(), 3);
+
+        let newer = json!({ "version": SAVE_VERSION + 1, "data": {} });
+        assert!(decode::<History>("history", newer).is_err());
+        let mangled = json!({ "version": SAVE_VERSION, "data": { "columns": 7 } });
+        assert!(decode::<GameState>("state", mangled).is_err());
+    }
+
+    #[test]
+    fn test_saved_games_migrate_their_contents() {
+        let mut list = json!([{ "state": 1, "history": 2 }, { "state": 3, "history": 4 }]);
+        let double = |value: Value| Ok(json!(value.as_u64().unwrap() * 2));
+        migrate_each(&mut list, "state", double).unwrap();
+        assert_eq!(
+            list,
+            json!([{ "state": 2, "history": 2 }, { "state": 6, "history": 4 }])
+        );
+        assert!(migrate_each(&mut list, "summary", double).is_err());
+
+        // unversioned saved games read as they are, along with the games in them
+        let slots = SaveSlots::default();
+        let loaded: SaveSlots = decode("saves", serde_json::to_value(&slots).unwrap()).unwrap();
+        assert!(loaded.games.is_empty());
+        let held = json!({ "games": [{ "id": "a" }, { "id": "b", "state": {}, "history": {} }] });
+        assert!(migrate("saves", 0, held).is_ok());
+        assert!(migrate("save:b", 0, json!({ "state": {}, "history": {} })).is_ok());
+        assert!(migrate("save:b", 0, json!({ "state": {} })).is_err());
+        let archive = json!([{ "history": {} }]);
+        assert!(migrate("archive", 0, archive).is_ok());
+    }
+}
@@ -0,0 +1,292 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri_plugin_store::Store;
+
+use super::{player::Player, save, stats::History, GameState};
+
+/// Summary of a saved game, to pick it out from the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveSummary {
+    pub id: String,
+    pub name: String,
+    /// When the game was started, in seconds since the Unix epoch.
+    pub created: u64,
+    /// When the game was last saved, in seconds since the Unix epoch.
+    pub updated: u64,
+    pub players: Vec<Player>,
+    /// Runs played so far by all players.
+    pub runs: usize,
+    /// Name of the winner, if the game is over.
+    pub winner: Option<String>,
+}
+
+/// A game kept in a save slot, with everything needed to carry on playing it.
+/// Each is kept under a key of its own, except the game being played,
+/// which is only kept as the game state and history until it's left.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub state: GameState,
+    pub history: History,
+}
+
+/// Every saved game, and which one is being played.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SaveSlots {
+    /// Id of the saved game being played, if it has been saved yet.
+    pub current: Option<String>,
+    pub games: Vec<SaveSummary>,
+}
+
+/// A saved game as it's read from the save slots, which held the game itself
+/// before each was kept under a key of its own.
+#[derive(Deserialize)]
+struct ListedGame {
+    #[serde(flatten)]
+    summary: SaveSummary,
+    #[serde(default)]
+    state: Option<GameState>,
+    #[serde(default)]
+    history: Option<History>,
+}
+
+/// The save slots as they're read, with any games still held in them.
+#[derive(Default, Deserialize)]
+struct ListedSlots {
+    current: Option<String>,
+    games: Vec<ListedGame>,
+}
+
+/// Store key prefix for a saved game, followed by its id.
+pub(super) const GAME_KEY_PREFIX: &str = "save:";
+
+/// Store key a saved game is kept under.
+fn game_key(id: &str) -> String {
+    format!("{GAME_KEY_PREFIX}{id}")
+}
+
+/// Seconds since the Unix epoch.
+pub(super) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Name for a game that hasn't been given one, from its players.
+fn default_name(players: &[Player]) -> String {
+    players
+        .iter()
+        .map(|player| player.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" vs ")
+}
+
+impl SaveSummary {
+    /// Summarise the game as it is now.
+    fn update(&mut self, state: &GameState, history: &History) {
+        self.updated = now();
+        self.players = state.settings.players.clone();
+        self.runs = history
+            .players
+            .iter()
+            .flat_map(|player| player.runs())
+            .filter(|run| !run.turns.is_empty())
+            .count();
+        self.winner = state.winner.as_ref().map(|winner| winner.name.clone());
+    }
+}
+
+impl SavedGame {
+    /// Read a saved game from disk.
+    pub fn read_from_store<R: tauri::Runtime>(store: &Store<R>, id: &str) -> anyhow::Result<Self> {
+        save::load(store, &game_key(id))?.ok_or_else(|| anyhow!("No saved game {}", id))
+    }
+    /// Save the game to disk, under its own key.
+    pub fn write_to_store<R: tauri::Runtime>(
+        &self,
+        store: &Store<R>,
+        id: &str,
+    ) -> anyhow::Result<()> {
+        save::save(store, &game_key(id), self)
+    }
+    /// Take a saved game off disk, once it's being played or has been deleted.
+    pub fn remove_from_store<R: tauri::Runtime>(store: &Store<R>, id: &str) {
+        store.delete(game_key(id));
+    }
+}
+
+impl SaveSlots {
+    /// Saved games, most recently played first.
+    pub fn list(&self) -> Vec<SaveSummary> {
+        let mut summaries = self.games.clone();
+        summaries.sort_by(|a, b| b.updated.cmp(&a.updated).then(a.name.cmp(&b.name)));
+        summaries
+    }
+    fn find(&mut self, id: &str) -> anyhow::Result<&mut SaveSummary> {
+        self.games
+            .iter_mut()
+            .find(|game| game.id == id)
+            .ok_or_else(|| anyhow!("No saved game {}", id))
+    }
+    /// Add a save slot for a game, named after its players if unnamed. Returns its id.
+    fn add(&mut self, state: &GameState, history: &History, name: Option<String>) -> String {
+        let time = now();
+        let id = format!("{:x}-{:04x}", time, rand::random::<u16>());
+        let players = state.settings.players.clone();
+        let name = name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| default_name(&players));
+        let mut summary = SaveSummary {
+            id: id.clone(),
+            name,
+            created: time,
+            updated: time,
+            players,
+            runs: 0,
+            winner: None,
+        };
+        summary.update(state, history);
+        self.games.push(summary);
+        id
+    }
+    /// Start a new save slot for the game being played, named after its players if unnamed.
+    pub fn start(&mut self, state: &GameState, history: &History, name: Option<String>) {
+        self.current = Some(self.add(state, history, name));
+    }
+    /// Add a save slot for a game, without playing it. Returns its id,
+    /// for the game to be kept under.
+    pub fn import(&mut self, state: &GameState, history: &History, name: String) -> String {
+        self.add(state, history, Some(name))
+    }
+    /// Update the summary of the game being played, starting a slot if it hasn't got one.
+    /// Returns the slot's id.
+    pub fn keep(&mut self, state: &GameState, history: &History) -> String {
+        if let Some(summary) = self.current.clone().and_then(|id| self.find(&id).ok()) {
+            summary.update(state, history);
+            return summary.id.clone();
+        }
+        let id = self.add(state, history, None);
+        self.current = Some(id.clone());
+        id
+    }
+    /// A saved game's summary.
+    pub fn get(&self, id: &str) -> anyhow::Result<&SaveSummary> {
+        self.games
+            .iter()
+            .find(|game| game.id == id)
+            .ok_or_else(|| anyhow!("No saved game {}", id))
+    }
+    /// Switch to playing a saved game, once it has been read from its key.
+    pub fn load(&mut self, id: &str) -> anyhow::Result<()> {
+        self.find(id)?;
+        self.current = Some(id.to_string());
+        Ok(())
+    }
+    /// Give a saved game a new name.
+    pub fn rename(&mut self, id: &str, name: String) -> anyhow::Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow!("A saved game needs a name"));
+        }
+        self.find(id)?.name = name;
+        Ok(())
+    }
+    /// Delete a saved game's slot. The game being played can't be deleted.
+    pub fn delete(&mut self, id: &str) -> anyhow::Result<()> {
+        if self.current.as_deref() == Some(id) {
+            return Err(anyhow!("Can't delete the game being played"));
+        }
+        self.find(id)?;
+        self.games.retain(|game| game.id != id);
+        Ok(())
+    }
+    /// Update saved games from disk. If they can't be loaded, they're backed up
+    /// and there are no saved games instead.
+    /// Games still held in the slots are moved under their own keys, apart from
+    /// the one being played, which is already kept as the game state and history.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        let listed: ListedSlots = match save::load(store, "saves") {
+            Ok(saves) => saves.unwrap_or_default(),
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
+        };
+        let mut moved = false;
+        self.current = listed.current;
+        self.games = Vec::with_capacity(listed.games.len());
+        for game in listed.games {
+            if let (Some(state), Some(history)) = (game.state, game.history) {
+                if self.current.as_ref() != Some(&game.summary.id) {
+                    SavedGame { state, history }.write_to_store(store, &game.summary.id)?;
+                }
+                moved = true;
+            }
+            self.games.push(game.summary);
+        }
+        if moved {
+            self.write_to_store(store)?;
+        }
+        Ok(())
+    }
+    /// Save the saved games' slots to disk
+    pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
+        save::save(store, "saves", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_slots_keep_games_apart() {
+        let mut slots = SaveSlots::default();
+        let mut grandparents = GameState::default();
+        grandparents.new_game(GameState::default().settings);
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        slots.start(&grandparents, &history, Some("Grandparents".into()));
+        let first = slots.current.clone().unwrap();
+        grandparents.winner = Some(grandparents.settings.players[0].clone());
+        assert_eq!(slots.keep(&grandparents, &history), first);
+
+        // The kids' game gets a slot of its own, named after the players.
+        slots.current = None;
+        slots.keep(&GameState::default(), &history);
+        assert_eq!(slots.games.len(), 2);
+        assert_eq!(slots.games[1].name, "Player 1 vs Player 2");
+        assert!(slots
+            .delete(slots.current.clone().unwrap().as_str())
+            .is_err());
+
+        slots.load(&first).unwrap();
+        assert_eq!(slots.current.as_deref(), Some(first.as_str()));
+        assert_eq!(
+            slots.get(&first).unwrap().winner.as_deref(),
+            Some("Player 1")
+        );
+        slots.rename(&first, "Grandma & Grandpa".into()).unwrap();
+        assert!(slots.rename(&first, " ".into()).is_err());
+        assert_eq!(slots.list().len(), 2);
+    }
+
+    #[test]
+    fn test_slots_read_the_games_they_held() {
+        let mut history = History::default();
+        history.new_game(2).unwrap();
+        let mut slots = SaveSlots::default();
+        slots.start(&GameState::default(), &history, None);
+        let saved = serde_json::to_value(&slots).unwrap();
+        let listed: ListedSlots = serde_json::from_value(saved.clone()).unwrap();
+        assert!(listed.games[0].state.is_none());
+
+        // Before each game had a key of its own, the slots held the games.
+        let mut held = saved;
+        held["games"][0]["state"] = serde_json::to_value(GameState::default()).unwrap();
+        held["games"][0]["history"] = serde_json::to_value(&history).unwrap();
+        let listed: ListedSlots = serde_json::from_value(held).unwrap();
+        assert_eq!(listed.games[0].summary.id, slots.games[0].id);
+        assert!(listed.games[0].state.is_some());
+        assert_eq!(listed.games[0].history.as_ref().unwrap().players.len(), 2);
+    }
+}
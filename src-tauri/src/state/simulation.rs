@@ -0,0 +1,338 @@
+use anyhow::anyhow;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, ops::Range};
+
+use super::{
+    logic::{evaluate_moves, throw_dice},
+    player::{Player, PlayerMode, RunOutcome},
+    stats::History,
+    Choice, DiceResult, GameState, PlayerID, SettingsState,
+};
+
+/// Upper limit on runs in a simulated game, in case the bots never finish.
+const MAX_RUNS: usize = 5_000;
+
+/// Makes a player's decisions in a simulated game, in place of their bot.
+pub trait Strategy {
+    /// Whether to roll again, once a hop has been made this run.
+    fn should_hop(&self, game: &GameState) -> bool;
+    /// Which of the offered choices (2d6) to take.
+    fn choose(&self, game: &GameState, choices: &HashSet<Choice>) -> Choice;
+}
+
+/// A headless game between bot players, using the same rules and bot logic as the app.
+pub struct Simulation {
+    pub game: GameState,
+    pub history: History,
+    rng: StdRng,
+}
+
+/// Summary of a finished simulated game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SimulationResult {
+    /// Index of the winning player, None if the game hit the run limit.
+    pub winner: Option<PlayerID>,
+    /// Total runs played by all players.
+    pub runs: usize,
+    /// Total turns (dice rolls) played by all players.
+    pub turns: usize,
+}
+
+impl Simulation {
+    /// Set up a new game between bots, with dice seeded for reproducibility.
+    pub fn new(settings: SettingsState, seed: u64) -> anyhow::Result<Self> {
+        let mut history = History::default();
+        history.new_game(settings.players.len())?;
+        let mut game = GameState::default();
+        game.new_game(settings);
+        Self::from_state(game, history, seed)
+    }
+    /// Continue an existing game with every player controlled by their bot.
+    pub fn from_state(game: GameState, history: History, seed: u64) -> anyhow::Result<Self> {
+        if let Some(human) = game
+            .settings
+            .players
+            .iter()
+            .find(|player| matches!(player.mode, PlayerMode::Human))
+        {
+            return Err(anyhow!("{} is human and can't be simulated", human.name));
+        }
+        Ok(Self {
+            game,
+            history,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+    /// Play out the current player's run until they bank or croak.
+    /// Returns the outcome and the number of dice rolls made.
+    pub fn play_run(&mut self) -> (RunOutcome, usize) {
+        self.play_run_with(None)
+    }
+    /// Play out the current player's run, with their bot or the given strategy deciding.
+    fn play_run_with(&mut self, strategy: Option<&dyn Strategy>) -> (RunOutcome, usize) {
+        let mut turns = 0;
+        let outcome = loop {
+            let hop = match strategy {
+                Some(strategy) => strategy.should_hop(&self.game),
+                None => self.game.bot_should_continue(),
+            };
+            // a run can only be banked once it has made a hop
+            if self.game.hops > 0 && !hop {
+                break RunOutcome::Banked;
+            }
+            let dice = throw_dice(&mut self.rng);
+            let selected = self.game.get_selected();
+            let choices = evaluate_moves(dice, &selected, &self.game.get_unavailable());
+            let result = DiceResult {
+                dice,
+                choices,
+                ..Default::default()
+            };
+            self.history.player_mut().record_roll(&result, &selected);
+            turns += 1;
+            if result.choices.is_empty() {
+                break RunOutcome::Croaked;
+            }
+            let (first, second) = match strategy {
+                Some(strategy) => strategy.choose(&self.game, &result.choices),
+                None => self.game.bot_choose(&result.choices),
+            };
+            // convert from 2d6 to index
+            let (first, second) = (first - 2, second.map(|x| x - 2));
+            self.game
+                .risk_columns(first, second)
+                .expect("players should only choose offered columns");
+            self.history.player_mut().record_choice(first, second);
+        };
+        self.game.next_player(outcome);
+        self.history
+            .next_player(outcome, self.game.get_unavailable());
+        (outcome, turns)
+    }
+    /// Play the game through to the end.
+    pub fn play(&mut self) -> SimulationResult {
+        self.play_with(None)
+    }
+    /// Play the game through to the end, with a strategy deciding for one player
+    /// and the bots for the rest.
+    pub fn play_against(&mut self, player: PlayerID, strategy: &dyn Strategy) -> SimulationResult {
+        self.play_with(Some((player, strategy)))
+    }
+    fn play_with(&mut self, strategy: Option<(PlayerID, &dyn Strategy)>) -> SimulationResult {
+        let mut runs = 0;
+        let mut turns = 0;
+        while self.game.winner.is_none() && runs < MAX_RUNS {
+            let current = self.game.current_player;
+            let strategy = strategy.filter(|(player, _)| *player == current);
+            turns += self.play_run_with(strategy.map(|(_, strategy)| strategy)).1;
+            runs += 1;
+        }
+        SimulationResult {
+            // the winner is left as the current player when the game ends
+            winner: self.game.winner.as_ref().map(|_| self.game.current_player),
+            runs,
+            turns,
+        }
+    }
+}
+
+/// Z score for a 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+/// A measured value with its 95% confidence interval.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Estimate {
+    pub value: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Estimate {
+    /// Wilson score interval for a success rate.
+    pub(super) fn proportion(successes: usize, trials: usize) -> Self {
+        if trials == 0 {
+            return Self {
+                value: 0.0,
+                low: 0.0,
+                high: 1.0,
+            };
+        }
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let z2 = Z_95 * Z_95;
+        let centre = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        Self {
+            value: p,
+            low: (centre - margin).max(0.0),
+            high: (centre + margin).min(1.0),
+        }
+    }
+    /// Normal approximation interval for a mean, from a running sum and sum of squares.
+    fn mean(sum: f64, sum_sq: f64, samples: usize) -> Self {
+        if samples == 0 {
+            return Self {
+                value: 0.0,
+                low: 0.0,
+                high: 0.0,
+            };
+        }
+        let n = samples as f64;
+        let mean = sum / n;
+        let variance = (sum_sq / n - mean * mean).max(0.0);
+        let margin = Z_95 * (variance / n).sqrt();
+        Self {
+            value: mean,
+            low: mean - margin,
+            high: mean + margin,
+        }
+    }
+}
+
+/// Results of a lineup of bots playing each other many times.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchupReport {
+    /// Bots taking part, in the order they were given.
+    pub lineup: Vec<PlayerMode>,
+    /// Total games played.
+    pub games: usize,
+    /// Wins for each bot in the lineup, wherever they were seated.
+    pub wins: Vec<usize>,
+    /// Wins for each seat, in turn order.
+    pub seat_wins: Vec<usize>,
+    /// Games that hit the run limit without a winner.
+    pub unfinished: usize,
+    /// Sum of runs over all games.
+    runs: f64,
+    /// Sum of squared runs over all games.
+    runs_sq: f64,
+}
+
+impl MatchupReport {
+    /// Play every seating rotation of the lineup once per seed.
+    /// The same seed is used for each rotation, so every bot faces the same dice from each seat.
+    pub fn play(lineup: &[PlayerMode], seeds: Range<u64>, win_cols: usize) -> anyhow::Result<Self> {
+        let seats = lineup.len();
+        let mut report = Self {
+            lineup: lineup.to_vec(),
+            games: 0,
+            wins: vec![0; seats],
+            seat_wins: vec![0; seats],
+            unfinished: 0,
+            runs: 0.0,
+            runs_sq: 0.0,
+        };
+        for seed in seeds {
+            for rotation in 0..seats {
+                // seat `i` is played by lineup entry `(i + rotation) % seats`
+                let players = (0..seats)
+                    .map(|seat| {
+                        let entry = (seat + rotation) % seats;
+                        Player::new(
+                            seat,
+                            lineup[entry],
+                            format!("Bot {} ({})", entry + 1, lineup[entry]),
+                        )
+                    })
+                    .collect();
+                let settings = SettingsState::new(players, win_cols);
+                let result = Simulation::new(settings, seed)?.play();
+                report.record(&result, rotation);
+            }
+        }
+        Ok(report)
+    }
+    /// Add the result of one game to the report.
+    fn record(&mut self, result: &SimulationResult, rotation: usize) {
+        let seats = self.lineup.len();
+        self.games += 1;
+        let runs = result.runs as f64;
+        self.runs += runs;
+        self.runs_sq += runs * runs;
+        match result.winner {
+            Some(seat) => {
+                self.seat_wins[seat] += 1;
+                self.wins[(seat + rotation) % seats] += 1;
+            }
+            None => self.unfinished += 1,
+        }
+    }
+    /// How often the given lineup entry won.
+    pub fn win_rate(&self, entry: usize) -> Estimate {
+        Estimate::proportion(self.wins[entry], self.games)
+    }
+    /// How often whoever went first won. A fair game is `1 / lineup.len()`.
+    pub fn first_player_win_rate(&self) -> Estimate {
+        Estimate::proportion(self.seat_wins[0], self.games)
+    }
+    /// Average number of runs taken by all players per game.
+    pub fn game_length(&self) -> Estimate {
+        Estimate::mean(self.runs, self.runs_sq, self.games)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bots(modes: &[PlayerMode]) -> SettingsState {
+        let players = modes
+            .iter()
+            .enumerate()
+            .map(|(id, &mode)| Player::new(id, mode, format!("Bot {id}")))
+            .collect();
+        SettingsState::new(players, 3)
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible() {
+        let settings = bots(&[PlayerMode::Safe, PlayerMode::Risky]);
+        let first = Simulation::new(settings.clone(), 7).unwrap().play();
+        let second = Simulation::new(settings, 7).unwrap().play();
+        assert!(first.winner.is_some());
+        assert_eq!(first.winner, second.winner);
+        assert_eq!(first.runs, second.runs);
+        assert_eq!(first.turns, second.turns);
+    }
+
+    #[test]
+    fn test_simulations_play_to_the_end() {
+        let modes = [
+            PlayerMode::Safe,
+            PlayerMode::Normal,
+            PlayerMode::Risky,
+            PlayerMode::Adaptive,
+        ];
+        for count in 2..=4 {
+            for seed in 0..50 {
+                let lineup: Vec<PlayerMode> = (0..count)
+                    .map(|seat| modes[(seat + seed as usize) % modes.len()])
+                    .collect();
+                let result = Simulation::new(bots(&lineup), seed).unwrap().play();
+                assert!(result.winner.is_some(), "{lineup:?} seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_simulation_rejects_humans() {
+        let settings = bots(&[PlayerMode::Normal, PlayerMode::Human]);
+        assert!(Simulation::new(settings, 0).is_err());
+    }
+
+    #[test]
+    fn test_matchup_counts_every_rotation() {
+        let lineup = [PlayerMode::Safe, PlayerMode::Normal, PlayerMode::Risky];
+        let report = MatchupReport::play(&lineup, 0..4, 3).unwrap();
+        assert_eq!(report.games, 12);
+        assert_eq!(
+            report.wins.iter().sum::<usize>(),
+            report.games - report.unfinished
+        );
+        assert_eq!(
+            report.wins.iter().sum::<usize>(),
+            report.seat_wins.iter().sum::<usize>()
+        );
+    }
+}
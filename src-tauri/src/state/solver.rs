@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap, sync::Mutex};
+
+use super::{
+    outlook::{RunExplorer, RunPosition},
+    Choice, GameState,
+};
+
+/// Number of boards whose solutions are kept, so every player's next run is already solved.
+const CACHED_SOLVERS: usize = 4;
+
+/// What a run is played for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Bank as much progress as possible, each hop worth its share of the column.
+    Progress,
+    /// Reach the top of a column, so it can be claimed.
+    ClaimColumn,
+}
+
+/// How to play the rest of a run for the best result.
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimalPlay {
+    pub objective: Objective,
+    /// Whether rolling again is worth more than stopping.
+    pub hop: bool,
+    /// Value of stopping now.
+    pub stop_value: f64,
+    /// Value of rolling again, and playing the rest of the run perfectly.
+    pub roll_value: f64,
+    /// Offered choices with the value of taking each, best first.
+    pub choices: Vec<(Choice, f64)>,
+}
+
+/// Works out the best way to play every run from a board position, for an objective.
+/// Progress risked this run is lost on croaking, so the value of a position is the best of
+/// stopping now, and the chance weighted value of the best choice on every roll.
+pub struct RunSolver {
+    objective: Objective,
+    explorer: RunExplorer,
+    /// Columns won, which can't be played (bit per column index).
+    inactive: u16,
+    /// Value of stopping and of rolling from each position solved so far.
+    solved: HashMap<RunPosition, (f64, f64)>,
+}
+
+impl RunSolver {
+    fn new(game: &GameState, objective: Objective) -> (Self, RunPosition) {
+        let (explorer, position) = RunExplorer::new(game);
+        let solver = Self {
+            objective,
+            explorer,
+            inactive: inactive_mask(game),
+            solved: HashMap::new(),
+        };
+        (solver, position)
+    }
+    /// Whether this solver is for the board the game's current player faces.
+    fn solves(&self, game: &GameState, objective: Objective) -> bool {
+        let player = game.current_player;
+        self.objective == objective
+            && self.inactive == inactive_mask(game)
+            && game
+                .columns
+                .iter()
+                .zip(self.explorer.remaining)
+                .all(|(column, remaining)| {
+                    column.height.saturating_sub(column.hops[player]) == remaining
+                })
+    }
+    /// Whether both solvers are for the same board.
+    fn same_board(&self, other: &Self) -> bool {
+        self.objective == other.objective
+            && self.inactive == other.inactive
+            && self.explorer.remaining == other.explorer.remaining
+    }
+    /// Value of banking the progress in a position.
+    fn stop_value(&self) -> impl Fn(&RunPosition) -> f64 {
+        let (objective, remaining, heights) = (
+            self.objective,
+            self.explorer.remaining,
+            self.explorer.heights,
+        );
+        move |position| match objective {
+            Objective::Progress => position.progress(&heights),
+            Objective::ClaimColumn => {
+                let topped = (0..11).any(|idx| {
+                    position.risked[idx] > 0 && position.risked[idx] as usize == remaining[idx]
+                });
+                if topped {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+    /// Value of stopping and of rolling from a position. Croaking loses everything.
+    fn solve(&mut self, position: RunPosition) -> (f64, f64) {
+        let stop_value = self.stop_value();
+        self.explorer
+            .solve_run(position, 0.0, &stop_value, &mut self.solved)
+    }
+}
+
+impl RunExplorer {
+    /// Value of stopping and of rolling from a position, playing the rest of the run perfectly,
+    /// given the value of stopping in each position and the value of croaking.
+    pub(super) fn solve_run(
+        &mut self,
+        position: RunPosition,
+        croak: f64,
+        stop_value: &dyn Fn(&RunPosition) -> f64,
+        solved: &mut HashMap<RunPosition, (f64, f64)>,
+    ) -> (f64, f64) {
+        if let Some(&values) = solved.get(&position) {
+            return values;
+        }
+        let stop = stop_value(&position);
+        // Every choice adds progress, apart from hops on topped columns, which lead
+        // straight back here. Solve the rest first, then settle the value of this position.
+        let moves = self.moves(&position);
+        let chances: Vec<f64> = self.chances().collect();
+        let mut rolls = Vec::with_capacity(chances.len());
+        for (chance, choices) in chances.into_iter().zip(moves.iter()) {
+            let mut best = None;
+            let mut stays = false;
+            for &choice in choices {
+                let next = self.play(&position, choice);
+                if next == position {
+                    stays = true;
+                } else {
+                    let (stop, roll) = self.solve_run(next, croak, stop_value, solved);
+                    let value = stop.max(roll);
+                    best = Some(best.map_or(value, |best: f64| best.max(value)));
+                }
+            }
+            rolls.push((chance, best, stays));
+        }
+        // Rolling croaks sometimes, so it's worth less each time around: this settles quickly.
+        let roll_value = |value: f64| -> f64 {
+            rolls
+                .iter()
+                .map(|&(chance, best, stays)| match (best, stays) {
+                    (None, false) => chance * croak,
+                    (best, stays) => {
+                        let stay = stays.then_some(value);
+                        chance * best.into_iter().chain(stay).fold(f64::MIN, f64::max)
+                    }
+                })
+                .sum()
+        };
+        let mut value = stop;
+        let mut roll = roll_value(value);
+        while stop.max(roll) - value > 1e-12 {
+            value = stop.max(roll);
+            roll = roll_value(value);
+        }
+        solved.insert(position, (stop, roll));
+        (stop, roll)
+    }
+}
+
+/// Columns won in a game, bit per column index.
+fn inactive_mask(game: &GameState) -> u16 {
+    game.get_unavailable()
+        .iter()
+        .fold(0, |mask, col| mask | (1 << (col - 2)))
+}
+
+/// Solvers for recently seen boards, most recently used last.
+#[derive(Default)]
+pub struct SolverCache(Vec<RunSolver>);
+
+impl SolverCache {
+    /// Take the solver for the current player's board out of the cache, reusing an earlier
+    /// one if possible, so it can be solved without holding the cache.
+    fn take(&mut self, game: &GameState, objective: Objective) -> (RunSolver, RunPosition) {
+        let (fresh, position) = RunSolver::new(game, objective);
+        let solver = match self.0.iter().position(|s| s.solves(game, objective)) {
+            Some(idx) => self.0.remove(idx),
+            None => fresh,
+        };
+        (solver, position)
+    }
+    /// Put a solver back as the most recently used. If the same board has been solved
+    /// meanwhile, the one with the most positions solved is kept.
+    fn keep(&mut self, solver: RunSolver) {
+        let solver = match self.0.iter().position(|s| s.same_board(&solver)) {
+            Some(idx) if self.0[idx].solved.len() >= solver.solved.len() => self.0.remove(idx),
+            Some(idx) => {
+                self.0.remove(idx);
+                solver
+            }
+            None => solver,
+        };
+        if self.0.len() >= CACHED_SOLVERS {
+            self.0.remove(0);
+        }
+        self.0.push(solver);
+    }
+}
+
+impl GameState {
+    /// How the current player should play the rest of their run for the objective,
+    /// and which of the offered `choices`, if any, to take.
+    /// Solving a new board takes a while, so the cache is only held to look it up.
+    pub fn optimal_play(
+        &self,
+        objective: Objective,
+        choices: Option<&[Choice]>,
+        cache: &Mutex<SolverCache>,
+    ) -> OptimalPlay {
+        let (mut solver, position) = cache.lock().unwrap().take(self, objective);
+        let (stop_value, roll_value) = solver.solve(position);
+        let mut choices: Vec<(Choice, f64)> = choices
+            .unwrap_or_default()
+            .iter()
+            .map(|&choice| {
+                let next = solver.explorer.play(&position, choice);
+                let (stop, roll) = solver.solve(next);
+                (choice, stop.max(roll))
+            })
+            .collect();
+        choices.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
+        cache.lock().unwrap().keep(solver);
+        OptimalPlay {
+            objective,
+            hop: roll_value > stop_value,
+            stop_value,
+            roll_value,
+            choices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{calculate_croak_chance, human_game, small_board};
+
+    #[test]
+    fn test_stop_once_column_topped() {
+        let mut game = human_game(2, 3);
+        // risking 2, 3 & 12, at the top of 2
+        game.columns[0].hops[0] = 2;
+        game.risk_columns(0, Some(1)).unwrap();
+        game.risk_columns(10, None).unwrap();
+        let cache = Mutex::default();
+        let play = game.optimal_play(Objective::ClaimColumn, None, &cache);
+        assert!(!play.hop);
+        assert_eq!(play.stop_value, 1.0);
+        // Rolling risks losing it all, for nothing more.
+        let croak = calculate_croak_chance(&game.get_selected(), &game.get_unavailable());
+        assert!(play.roll_value <= 1.0 - croak + 1e-9);
+    }
+
+    #[test]
+    fn test_hop_at_start_of_run() {
+        let mut game = small_board();
+        let cache = Mutex::default();
+        let play = game.optimal_play(Objective::Progress, None, &cache);
+        assert!(play.hop);
+        assert_eq!(play.stop_value, 0.0);
+
+        // The same board is solved once, and the next roll ranks its choices.
+        game.risk_columns(5, None).unwrap();
+        let choices = [(2, Some(12)), (6, Some(8))];
+        let play = game.optimal_play(Objective::Progress, Some(&choices), &cache);
+        assert_eq!(cache.lock().unwrap().0.len(), 1);
+        // Hops on the short columns are each worth a third of the column.
+        assert_eq!(play.choices[0].0, (2, Some(12)));
+    }
+}
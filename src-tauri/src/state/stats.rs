@@ -1,8 +1,9 @@
 use crate::state::{columns::HEIGHTS, logic::calculate_croak_chance};
 
 use super::{
+    forecast::WinChances,
     player::{PlayerRun, PlayerStats, RunOutcome},
-    ColumnID, DiceResult, PlayerID,
+    save, ColumnID, DiceResult, PlayerID,
 };
 use anyhow::anyhow;
 use core::panic;
@@ -21,6 +22,10 @@ pub type HistoryMutex = Mutex<History>;
 pub struct PlayerHistory(Vec<PlayerRun>);
 
 impl PlayerHistory {
+    /// Every run this player has started, in order.
+    pub fn runs(&self) -> &[PlayerRun] {
+        &self.0
+    }
     /// Register the start of a new turn for this player.
     pub fn record_start_run(&mut self, inactive_cols: HashSet<ColumnID>) {
         self.0.push(PlayerRun::start(inactive_cols));
@@ -38,14 +43,22 @@ impl PlayerHistory {
         let run = self.run_mut();
         run.start_turn(dice.to_owned(), active_cols.to_owned());
     }
+    /// The latest roll in the run in progress, if any.
+    pub fn last_roll(&self) -> Option<&DiceResult> {
+        Some(&self.0.last()?.turns.last()?.options)
+    }
     /// Record the choice from the dice roll and options for the active player's latest turn.
     pub fn record_choice(&mut self, first: ColumnID, second: Option<ColumnID>) {
         self.run_mut().turn_mut().chosen = Some((first, second));
     }
     /// Record the outcome of this run when it ends for any reason.
     fn record_end_run(&mut self, outcome: RunOutcome) {
-        self.run_mut().outcome = outcome;
-        self.run_mut().turn_mut().outcome = outcome;
+        let run = self.run_mut();
+        run.outcome = outcome;
+        // a run can end before its first roll, if the game is left mid-run
+        if let Some(turn) = run.turns.last_mut() {
+            turn.outcome = outcome;
+        }
     }
 }
 
@@ -56,6 +69,10 @@ pub struct History {
     pub players: Vec<PlayerHistory>,
     /// index of current player
     pub current_player: PlayerID,
+    /// Each player's chance of winning (0.0 - 1.0) at the start of the game
+    /// and after every run, by player index. Empty where it couldn't be estimated.
+    #[serde(default)]
+    pub win_timeline: Vec<Vec<f64>>,
 }
 
 impl History {
@@ -73,12 +90,20 @@ impl History {
         }
         self.players = vec![PlayerHistory::default(); num_players];
         self.current_player = 0;
+        self.win_timeline.clear();
         // Start the first player's turn in history
         let player = self.player_mut();
         player.record_start_run(HashSet::new());
         Ok(())
     }
 
+    /// Gets the history of the current player.
+    pub fn player(&self) -> &PlayerHistory {
+        self.players
+            .get(self.current_player)
+            .expect("current player should always exist.")
+    }
+
     /// Gets a mutable reference to the history of the specified player.
     pub fn player_mut(&mut self) -> &mut PlayerHistory {
         self.players
@@ -93,20 +118,60 @@ impl History {
         self.player_mut().record_start_run(inactive_cols);
     }
 
-    /// Update game history from disk
-    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) {
-        if let Some(history) = store.get("history") {
-            *self = serde_json::from_value(history).unwrap_or_default();
-        } else {
-            println!("'history' missing from store");
-            *self = Self::default();
+    /// Record each player's chance of winning, for the timeline.
+    pub fn record_win_chances(&mut self, chances: &WinChances) {
+        self.win_timeline.push(chances.values());
+    }
+
+    /// Leave a gap in the timeline for chances that couldn't be estimated,
+    /// so the ones after still line up with their runs.
+    pub fn skip_win_chances(&mut self) {
+        self.win_timeline.push(Vec::new());
+    }
+
+    /// Every run started, with the index of the player taking it, in the order they were played.
+    pub fn runs_in_order(&self) -> Vec<(PlayerID, &PlayerRun)> {
+        let mut runs = vec![];
+        'rounds: for round in 0.. {
+            for (player, player_history) in self.players.iter().enumerate() {
+                let Some(run) = player_history.runs().get(round) else {
+                    break 'rounds;
+                };
+                runs.push((player, run));
+            }
+        }
+        runs
+    }
+
+    /// The most recent run a player has finished, if any.
+    pub fn last_finished_run(&self, player: PlayerID) -> Option<&PlayerRun> {
+        self.players
+            .get(player)?
+            .0
+            .iter()
+            .rev()
+            .find(|run| run.outcome != RunOutcome::InProgress)
+    }
+
+    /// Update game history from disk, migrating it from older save versions.
+    /// If it can't be loaded, it's backed up and a fresh game history is used instead.
+    pub fn update_from_store<R: tauri::Runtime>(&mut self, store: &Store<R>) -> anyhow::Result<()> {
+        match save::load(store, "history") {
+            Ok(Some(history)) => *self = history,
+            Ok(None) => {
+                println!("'history' missing from store");
+                *self = Self::default();
+            }
+            Err(e) => {
+                *self = Self::default();
+                return Err(e);
+            }
         }
+        Ok(())
     }
     /// Save game history to disk
     pub fn write_to_store<R: tauri::Runtime>(&self, store: &Store<R>) -> anyhow::Result<()> {
-        let history = serde_json::to_value(self.clone())?;
-        store.set("history", history);
-        Ok(())
+        save::save(store, "history", self)
     }
 
     /// Calculates and returns the end-of-game statistics summary.
@@ -179,6 +244,7 @@ impl History {
             player_stats,
             most_contested_column: most_contested_columm.0,
             total_turns,
+            win_timeline: self.win_timeline.clone(),
         }
     }
 }
@@ -206,8 +272,6 @@ impl Display for History {
     }
 }
 
-/// End game statistics for a specific player
-
 /// Holds the calculated statistics for a completed game.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatsSummary {
@@ -216,4 +280,7 @@ pub struct StatsSummary {
     /// Column that had the most total hops, normalized for column height
     pub most_contested_column: ColumnID,
     pub total_turns: usize,
+    /// Each player's chance of winning at the start of the game and after every run,
+    /// empty where it couldn't be estimated.
+    pub win_timeline: Vec<Vec<f64>>,
 }
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::Mutex};
+
+use super::{
+    logic::calculate_croak_chance,
+    review::{Action, MISTAKE_EQUITY},
+    solver::SolverCache,
+    Choice, GameState,
+};
+
+/// Progress given away, in columns' worth of hops, before a decision stops being fine.
+const FINE_EQUITY: f64 = 0.02;
+/// Progress given away, in columns' worth of hops, before a decision is a blunder.
+const BLUNDER_EQUITY: f64 = 0.15;
+
+/// How a decision compares with the solver's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grade {
+    /// The best play.
+    Excellent,
+    /// Close enough to the best play not to matter much.
+    Fine,
+    /// Gives away progress by taking on more risk than the best play.
+    Risky,
+    /// Gives away a lot of progress.
+    Blunder,
+}
+
+/// Feedback on a human's decision, for training mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feedback {
+    pub grade: Grade,
+    pub played: Action,
+    pub best: Action,
+    /// Progress given away, in columns' worth of hops.
+    pub equity_lost: f64,
+    pub explanation: String,
+}
+
+/// Describe a choice (2d6), e.g. "6 & 8".
+fn describe(choice: Choice) -> String {
+    match choice {
+        (first, Some(second)) => format!("{first} & {second}"),
+        (first, None) => format!("{first}"),
+    }
+}
+
+impl GameState {
+    /// Chance (0.0 - 1.0) of croaking on the next roll after taking a choice (2d6).
+    fn croak_chance_after(&self, (first, second): Choice) -> f64 {
+        let mut active = self.get_selected();
+        active.extend([Some(first), second].into_iter().flatten());
+        calculate_croak_chance(&active, &self.get_unavailable())
+    }
+    /// Grade the current player banking their run, before it's banked.
+    /// None if nothing has been risked, as there was nothing to decide.
+    pub fn grade_stop(&self, cache: &Mutex<SolverCache>) -> Option<Feedback> {
+        let (best, equity_lost) = self.judge_hop(Action::Stop, cache)?;
+        let croak = calculate_croak_chance(&self.get_selected(), &self.get_unavailable()) * 100.0;
+        let (grade, explanation) = match equity_lost {
+            lost if lost <= MISTAKE_EQUITY => (
+                Grade::Excellent,
+                format!(
+                    "Good time to stop: another roll has a {croak:.0}% chance of croaking, \
+                     which outweighs what it would add."
+                ),
+            ),
+            lost if lost < BLUNDER_EQUITY => (
+                Grade::Fine,
+                format!(
+                    "A little cautious: another roll was worth {lost:.2} columns more, \
+                     with a {croak:.0}% chance of croaking."
+                ),
+            ),
+            lost => (
+                Grade::Blunder,
+                format!(
+                    "Stopped too soon: another roll was worth {lost:.2} columns more, \
+                     with only a {croak:.0}% chance of croaking."
+                ),
+            ),
+        };
+        Some(Feedback {
+            grade,
+            played: Action::Stop,
+            best,
+            equity_lost,
+            explanation,
+        })
+    }
+    /// Grade the current player taking one of the offered `choices` (2d6), before it's taken.
+    /// None if there was only one choice to take.
+    pub fn grade_choice(
+        &self,
+        choices: &HashSet<Choice>,
+        chosen: Choice,
+        cache: &Mutex<SolverCache>,
+    ) -> Option<Feedback> {
+        if choices.len() < 2 {
+            return None;
+        }
+        let (best, equity_lost) = self.judge_choice(choices, chosen, cache);
+        let played_croak = self.croak_chance_after(chosen);
+        let best_croak = self.croak_chance_after(best);
+        let grade = match equity_lost {
+            lost if lost <= MISTAKE_EQUITY => Grade::Excellent,
+            lost if lost < FINE_EQUITY => Grade::Fine,
+            lost if lost < BLUNDER_EQUITY && played_croak > best_croak => Grade::Risky,
+            lost if lost < BLUNDER_EQUITY => Grade::Fine,
+            _ => Grade::Blunder,
+        };
+        let explanation = match grade {
+            Grade::Excellent => format!(
+                "The best choice, leaving a {:.0}% chance of croaking next roll.",
+                played_croak * 100.0
+            ),
+            _ => format!(
+                "{} was worth {equity_lost:.2} columns more, with a {:.0}% chance of croaking \
+                 next roll to your {:.0}%.",
+                describe(best),
+                best_croak * 100.0,
+                played_croak * 100.0
+            ),
+        };
+        Some(Feedback {
+            grade,
+            played: Action::Choose(chosen),
+            best: Action::Choose(best),
+            equity_lost,
+            explanation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::small_board;
+
+    #[test]
+    fn test_grade_stop_too_soon() {
+        let mut game = small_board();
+        let cache = Mutex::default();
+        assert!(game.grade_stop(&cache).is_none());
+
+        // A single hop on 7, with a good chance of rolling on from there.
+        game.risk_columns(5, None).unwrap();
+        let feedback = game.grade_stop(&cache).unwrap();
+        assert_eq!(feedback.best, Action::Hop);
+        assert_eq!(feedback.grade, Grade::Blunder);
+
+        let choices = HashSet::from([(2, Some(12)), (6, Some(8))]);
+        let feedback = game.grade_choice(&choices, (2, Some(12)), &cache).unwrap();
+        assert_eq!(feedback.grade, Grade::Excellent);
+    }
+}
@@ -0,0 +1,380 @@
+use anyhow::{anyhow, Context as _};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+};
+
+use super::{
+    columns::HEIGHTS,
+    outlook::{RunExplorer, RunPosition},
+    personality::Personality,
+    simulation::{Simulation, Strategy},
+    stats::History,
+    Choice, ColumnID, GameState, Player, PlayerID, PlayerMode, SettingsState,
+};
+
+/// Identifies a solved table file, and its layout version.
+const MAGIC: &[u8; 4] = b"CHT1";
+/// Most sweeps over every position before giving up on the values settling.
+const MAX_SWEEPS: usize = 10_000;
+/// Largest change in any value in a sweep for the values to have settled.
+const TOLERANCE: f64 = 1e-10;
+
+/// A reduced two player board: a few of the columns, with both players starting
+/// `length` hops from the top of each. The rest of the columns are closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    /// Columns (2d6) in play.
+    pub columns: Vec<ColumnID>,
+    /// Hops from the bottom to the top of each column.
+    pub length: usize,
+    /// Columns required to win.
+    pub win_cols: usize,
+}
+
+/// Hops each player has made up each of a variant's columns, the player to move first.
+type Position = Vec<(usize, usize)>;
+
+/// The same position, from the other player's side.
+fn swap(position: &[(usize, usize)]) -> Position {
+    position.iter().map(|&(a, b)| (b, a)).collect()
+}
+
+impl Variant {
+    pub fn new(mut columns: Vec<ColumnID>, length: usize, win_cols: usize) -> anyhow::Result<Self> {
+        columns.sort_unstable();
+        columns.dedup();
+        if columns.is_empty() || columns.len() > 4 {
+            return Err(anyhow!("a variant needs 1 to 4 columns"));
+        }
+        if let Some(col) = columns.iter().find(|col| !(2..=12).contains(*col)) {
+            return Err(anyhow!("{} is not a column", col));
+        }
+        let shortest = columns
+            .iter()
+            .map(|col| HEIGHTS[col - 2])
+            .min()
+            .unwrap_or(0);
+        if !(1..=shortest).contains(&length) {
+            return Err(anyhow!("length should be 1 to {}", shortest));
+        }
+        if !(1..=columns.len()).contains(&win_cols) {
+            return Err(anyhow!("win columns should be 1 to {}", columns.len()));
+        }
+        Ok(Self {
+            columns,
+            length,
+            win_cols,
+        })
+    }
+    /// Number of positions, including those that can't happen in a game.
+    pub fn positions(&self) -> usize {
+        (self.length + 1).pow(2 * self.columns.len() as u32)
+    }
+    fn encode(&self, position: &[(usize, usize)]) -> usize {
+        let base = self.length + 1;
+        position.iter().fold(0, |index, &(mover, other)| {
+            (index * base + mover) * base + other
+        })
+    }
+    fn decode(&self, mut index: usize) -> Position {
+        let base = self.length + 1;
+        let mut position = vec![(0, 0); self.columns.len()];
+        for hops in position.iter_mut().rev() {
+            hops.1 = index % base;
+            index /= base;
+            hops.0 = index % base;
+            index /= base;
+        }
+        position
+    }
+    /// Columns won by the player to move and by the other player.
+    fn won(&self, position: &[(usize, usize)]) -> (usize, usize) {
+        let won = |hops: usize| (hops == self.length) as usize;
+        position.iter().fold((0, 0), |(mover, other), &(a, b)| {
+            (mover + won(a), other + won(b))
+        })
+    }
+    /// Position of a game being played on the variant's board, from the side of the player
+    /// to move. None if it's being played on another board.
+    pub fn position(&self, game: &GameState) -> Option<Position> {
+        if game.settings.players.len() != 2 || game.settings.win_cols != self.win_cols {
+            return None;
+        }
+        let open = game.columns.iter().filter(|column| column.locked.is_none());
+        if !open
+            .into_iter()
+            .all(|column| self.columns.contains(&column.col))
+        {
+            return None;
+        }
+        let (mover, other) = (game.current_player, 1 - game.current_player);
+        self.columns
+            .iter()
+            .map(|&col| {
+                let column = &game.columns[col - 2];
+                let bottom = column.height - self.length;
+                let hops = |player: PlayerID| column.hops[player].checked_sub(bottom);
+                Some((hops(mover)?, hops(other)?))
+            })
+            .collect()
+    }
+    /// Chance of the player to move winning by banking a run from a position,
+    /// given the chance of the player to move winning from each position.
+    fn bank_value(
+        &self,
+        position: &[(usize, usize)],
+        run: &RunPosition,
+        value: impl Fn(&[(usize, usize)]) -> f64,
+    ) -> f64 {
+        let mut banked = position.to_vec();
+        for (hops, col) in banked.iter_mut().zip(&self.columns) {
+            hops.0 += run.risked[col - 2] as usize;
+        }
+        if self.won(&banked).0 >= self.win_cols {
+            return 1.0;
+        }
+        1.0 - value(&swap(&banked))
+    }
+    /// The game, with the player to move first, for a position.
+    pub fn game_state(&self, position: &[(usize, usize)]) -> GameState {
+        let players = (0..2)
+            .map(|id| Player::new(id, PlayerMode::Human, format!("Player {}", id + 1)))
+            .collect();
+        let mut game = GameState::default();
+        game.new_game(SettingsState::new(players, self.win_cols));
+        for column in game.columns.iter_mut() {
+            // Closed, as if already won
+            column.locked = Some(1);
+        }
+        for (&col, &(mover, other)) in self.columns.iter().zip(position) {
+            let column = &mut game.columns[col - 2];
+            let bottom = column.height - self.length;
+            column.hops[0] = bottom + mover;
+            column.hops[1] = bottom + other;
+            column.locked = match (mover == self.length, other == self.length) {
+                (true, _) => Some(0),
+                (_, true) => Some(1),
+                _ => None,
+            };
+        }
+        game
+    }
+}
+
+/// Chance of the player to move winning every position of a variant, playing perfectly.
+pub struct SolvedVariant {
+    pub variant: Variant,
+    /// By position, to the nearest 1/65535.
+    values: Vec<u16>,
+}
+
+impl SolvedVariant {
+    /// Chance (0.0 - 1.0) of the player to move winning from a position.
+    pub fn value(&self, position: &[(usize, usize)]) -> f64 {
+        self.values[self.variant.encode(position)] as f64 / u16::MAX as f64
+    }
+    /// Chance (0.0 - 1.0) of the first player winning from the start of the game.
+    pub fn first_player_wins(&self) -> f64 {
+        self.value(&vec![(0, 0); self.variant.columns.len()])
+    }
+    /// Solve a variant by value iteration: each position is worth the best the player
+    /// to move can do with a run, where banking or croaking hands the other player their
+    /// position. Returns the solution and the number of sweeps taken to settle.
+    pub fn solve(variant: Variant) -> anyhow::Result<(Self, usize)> {
+        let count = variant.positions();
+        let mut values = vec![0.5; count];
+        // Each position still being played, with an explorer for the runs from it.
+        let mut playable = vec![];
+        for (index, value) in values.iter_mut().enumerate() {
+            let position = variant.decode(index);
+            let clash = position
+                .iter()
+                .any(|&(a, b)| a == variant.length && b == variant.length);
+            match variant.won(&position) {
+                _ if clash => (), // both topped the same column, can't happen
+                (_, other) if other >= variant.win_cols => *value = 0.0,
+                (mover, _) if mover >= variant.win_cols => *value = 1.0,
+                _ => {
+                    let game = variant.game_state(&position);
+                    playable.push((index, position, RunExplorer::new(&game)));
+                }
+            }
+        }
+        for sweep in 1..=MAX_SWEEPS {
+            let mut change: f64 = 0.0;
+            for (index, position, (explorer, start)) in playable.iter_mut() {
+                // Croaking hands over the same position.
+                let croak = 1.0 - values[variant.encode(&swap(position))];
+                let bank = |run: &RunPosition| {
+                    variant.bank_value(position, run, |banked| values[variant.encode(banked)])
+                };
+                let (stop, roll) = explorer.solve_run(*start, croak, &bank, &mut HashMap::new());
+                let value = stop.max(roll);
+                change = change.max((value - values[*index]).abs());
+                values[*index] = value;
+            }
+            if change < TOLERANCE {
+                let values = values
+                    .iter()
+                    .map(|value| (value * u16::MAX as f64).round() as u16)
+                    .collect();
+                return Ok((Self { variant, values }, sweep));
+            }
+        }
+        Err(anyhow!("values didn't settle in {} sweeps", MAX_SWEEPS))
+    }
+    /// Chance (0.0 - 1.0) of the player to move winning by stopping, and by rolling on,
+    /// from where their run is in a game on the variant's board, after taking `choice` (2d6)
+    /// if there is one. None if the game is being played on another board.
+    fn run_values(&self, game: &GameState, choice: Option<Choice>) -> Option<(f64, f64)> {
+        let position = self.variant.position(game)?;
+        let (mut explorer, mut run) = RunExplorer::new(game);
+        if let Some(choice) = choice {
+            run = explorer.play(&run, choice);
+        }
+        // Croaking hands over the position the run started from.
+        let croak = 1.0 - self.value(&swap(&position));
+        let bank = |run: &RunPosition| {
+            self.variant
+                .bank_value(&position, run, |next| self.value(next))
+        };
+        Some(explorer.solve_run(run, croak, &bank, &mut HashMap::new()))
+    }
+    /// Play a game on the variant's board between the perfect player, seated at `player`,
+    /// and a bot. Returns the index of the winner, None if the game hit the run limit.
+    pub fn play_against(
+        &self,
+        bot: PlayerMode,
+        player: PlayerID,
+        seed: u64,
+    ) -> anyhow::Result<Option<PlayerID>> {
+        if player > 1 {
+            return Err(anyhow!("the perfect player can only sit first or second"));
+        }
+        let mut game = self
+            .variant
+            .game_state(&vec![(0, 0); self.variant.columns.len()]);
+        for (index, seat) in game.settings.players.iter_mut().enumerate() {
+            // the perfect player's mode is never used, but it can't be human
+            seat.mode = bot;
+            seat.name = match index == player {
+                true => "Perfect".to_string(),
+                false => format!("{bot} Bot"),
+            };
+        }
+        let mut history = History::default();
+        history.new_game(2)?;
+        let mut simulation = Simulation::from_state(game, history, seed)?;
+        Ok(simulation.play_against(player, self).winner)
+    }
+    /// Write the solution to a compact file: a header describing the variant, then the
+    /// value of each position as a little endian u16.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let variant = &self.variant;
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(variant.length as u8);
+        bytes.push(variant.win_cols as u8);
+        bytes.push(variant.columns.len() as u8);
+        bytes.extend(variant.columns.iter().map(|&col| col as u8));
+        bytes.extend(self.values.iter().flat_map(|value| value.to_le_bytes()));
+        std::fs::write(path, bytes).with_context(|| format!("writing {}", path.display()))
+    }
+    /// Read a solution written by `write`.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let header = bytes
+            .strip_prefix(MAGIC)
+            .context("not a solved variant file")?;
+        let [length, win_cols, count, rest @ ..] = header else {
+            return Err(anyhow!("solved variant file is truncated"));
+        };
+        let (columns, values) = rest
+            .split_at_checked(*count as usize)
+            .context("solved variant file is truncated")?;
+        let columns = columns.iter().map(|&col| col as ColumnID).collect();
+        let variant = Variant::new(columns, *length as usize, *win_cols as usize)?;
+        if values.len() != variant.positions() * 2 {
+            return Err(anyhow!(
+                "solved variant file has the wrong number of positions"
+            ));
+        }
+        let values = values
+            .chunks_exact(2)
+            .map(|value| u16::from_le_bytes([value[0], value[1]]))
+            .collect();
+        Ok(Self { variant, values })
+    }
+}
+
+/// Plays perfectly on the variant's board. Off it, plays like the Normal bot.
+impl Strategy for SolvedVariant {
+    fn should_hop(&self, game: &GameState) -> bool {
+        match self.run_values(game, None) {
+            Some((stop, roll)) => roll > stop,
+            None => game.explain_hop(&Personality::NORMAL).hop,
+        }
+    }
+    fn choose(&self, game: &GameState, choices: &HashSet<Choice>) -> Choice {
+        if self.variant.position(game).is_none() {
+            return game.rank_choices(&Personality::NORMAL, choices)[0].choice;
+        }
+        // in order, so ties always go the same way
+        let choices: BTreeSet<Choice> = choices.iter().copied().collect();
+        let mut best = None;
+        for choice in choices {
+            let Some((stop, roll)) = self.run_values(game, Some(choice)) else {
+                continue;
+            };
+            let value = stop.max(roll);
+            if best.is_none_or(|(_, best)| value > best) {
+                best = Some((choice, value));
+            }
+        }
+        best.expect("there's always a choice to take").0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::calculate_roll_odds;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_solve_single_column() {
+        // Whoever first rolls a 7 wins: p + (1 - p)(1 - v) = v
+        let variant = Variant::new(vec![7], 1, 1).unwrap();
+        let (solved, _) = SolvedVariant::solve(variant.clone()).unwrap();
+        let closed: HashSet<ColumnID> = (2..=12).filter(|&col| col != 7).collect();
+        let hit = calculate_roll_odds(&HashSet::new(), &closed).hit_chances[5];
+        let expected = 1.0 / (2.0 - hit);
+        assert!((solved.first_player_wins() - expected).abs() < 1e-4);
+
+        let path = std::env::temp_dir().join("cant-hop-test-variant.bin");
+        solved.write(&path).unwrap();
+        let read = SolvedVariant::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.variant, variant);
+        assert_eq!(read.values, solved.values);
+    }
+
+    #[test]
+    fn test_perfect_play_beats_bots() {
+        let variant = Variant::new(vec![6, 7, 8], 2, 2).unwrap();
+        let (solved, _) = SolvedVariant::solve(variant.clone()).unwrap();
+        let start = variant.game_state(&[(0, 0); 3]);
+        assert_eq!(variant.position(&start), Some(vec![(0, 0); 3]));
+        assert_eq!(variant.position(&GameState::default()), None);
+
+        let mut won = 0;
+        for seed in 0..40 {
+            let player = seed as usize % 2;
+            let winner = solved
+                .play_against(PlayerMode::Risky, player, seed)
+                .unwrap();
+            won += (winner == Some(player)) as usize;
+        }
+        assert!(won > 24, "the perfect player won {won} of 40");
+    }
+}
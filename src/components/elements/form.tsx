@@ -1,14 +1,60 @@
 /** Settings for the game.  This page is loaded once before a game starts to determine rules and player count. */
 import { useForm } from "@tanstack/react-form";
 import { motion } from "motion/react";
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { GiFrogPrince } from "react-icons/gi";
-import { getName, startGame } from "services/ipc";
-import { PlayerColors, PlayerMode, SettingsState } from "types";
+import {
+  getName,
+  getPersonalities,
+  getProfiles,
+  startGame,
+} from "services/ipc";
+import {
+  Personalities,
+  PlayerColors,
+  PlayerMode,
+  Profiles,
+  SettingsState,
+} from "types";
+
+/** Prefix used in the mode select to identify saved custom personalities. */
+const CUSTOM_PREFIX = "Custom:";
 
 function PlayerForm({ first, second }: { first: string; second: string }) {
   // used for name input field on focus and blur
   const [tempName, setTempName] = useState<string>("");
+  // saved custom bot personalities, selectable as player modes
+  const [personalities, setPersonalities] = useState<Personalities>({});
+  // name for the saved game, named after the players if left empty
+  const [gameName, setGameName] = useState<string>("");
+
+  // regular players, who can be picked instead of typing a name
+  const [profiles, setProfiles] = useState<Profiles>({});
+
+  useEffect(() => {
+    getPersonalities().then(setPersonalities);
+    getProfiles()
+      .then(setProfiles)
+      .catch((e) => console.error("Couldn't load profiles", e));
+  }, []);
+
+  /** Convert a player mode into a value for the mode select. */
+  const modeToValue = (mode: PlayerMode): string => {
+    if (typeof mode === "string") return mode;
+    const name = Object.entries(personalities).find(
+      ([, personality]) =>
+        JSON.stringify(personality) === JSON.stringify(mode.Custom)
+    )?.[0];
+    return `${CUSTOM_PREFIX}${name ?? ""}`;
+  };
+
+  /** Convert a value from the mode select into a player mode. */
+  const valueToMode = (value: string): PlayerMode => {
+    if (value.startsWith(CUSTOM_PREFIX)) {
+      return { Custom: personalities[value.slice(CUSTOM_PREFIX.length)] };
+    }
+    return value as PlayerMode;
+  };
 
   const defaultValues: SettingsState = {
     players: [
@@ -26,11 +72,12 @@ function PlayerForm({ first, second }: { first: string; second: string }) {
       },
     ],
     win_cols: 3,
+    training: false,
   };
   const form = useForm({
     defaultValues,
     onSubmit: (values) => {
-      startGame(values.value).then((success) => {
+      startGame(values.value, gameName || undefined).then((success) => {
         if (!success) {
           alert("Failed to start game");
           alert(JSON.stringify(values.value));
@@ -116,19 +163,49 @@ function PlayerForm({ first, second }: { first: string; second: string }) {
                       />
                     )}
                   </form.Field>
+                  <form.Field name={`players[${index}]`}>
+                    {(subField) => (
+                      <select
+                        className="h-full border rounded select min-w-fit"
+                        value={subField.state.value.profile ?? ""}
+                        onChange={(e) => {
+                          const profile = profiles[e.target.value];
+                          subField.setValue({
+                            ...subField.state.value,
+                            profile: profile?.id,
+                            name: profile?.name ?? subField.state.value.name,
+                            mode: profile?.preferred_mode ?? subField.state.value.mode,
+                          });
+                        }}
+                      >
+                        <option value="">Guest</option>
+                        {Object.values(profiles).map((profile) => (
+                          <option key={profile.id} value={profile.id}>
+                            {profile.avatar} {profile.name}
+                          </option>
+                        ))}
+                      </select>
+                    )}
+                  </form.Field>
                   <form.Field name={`players[${index}].mode`}>
                     {(subField) => (
                       <select
                         className="h-full border rounded select min-w-fit"
-                        value={subField.state.value}
+                        value={modeToValue(subField.state.value)}
                         onChange={(e) =>
-                          subField.setValue(e.target.value as PlayerMode)
+                          subField.setValue(valueToMode(e.target.value))
                         }
                       >
                         <option value="Human">Human</option>
                         <option value="Safe">AI: Safe</option>
                         <option value="Normal">AI: Normal</option>
                         <option value="Risky">AI: Risky</option>
+                        <option value="Adaptive">AI: Adaptive</option>
+                        {Object.keys(personalities).map((name) => (
+                          <option key={name} value={`${CUSTOM_PREFIX}${name}`}>
+                            AI: {name}
+                          </option>
+                        ))}
                       </select>
                     )}
                   </form.Field>
@@ -152,6 +229,28 @@ function PlayerForm({ first, second }: { first: string; second: string }) {
                   </select>
                 )}
               </form.Field>
+
+              <h2 className="m-2 text-xl font-bold">Game Name</h2>
+              <input
+                className="input border rounded mb-6 w-fit"
+                placeholder="Named after the players"
+                value={gameName}
+                onChange={(e) => setGameName(e.target.value)}
+              />
+
+              <form.Field name="training">
+                {(field) => (
+                  <label className="label cursor-pointer mb-6 w-fit">
+                    <input
+                      type="checkbox"
+                      className="checkbox mr-2"
+                      checked={field.state.value ?? false}
+                      onChange={(e) => field.setValue(e.target.checked)}
+                    />
+                    Training mode: grade each decision as you play
+                  </label>
+                )}
+              </form.Field>
             </div>
           )}
         </form.Field>
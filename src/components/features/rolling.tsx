@@ -1,14 +1,49 @@
 import React, { useState, useCallback } from "react";
-import { chooseColumns, endRun, rollDice } from "services/ipc";
-import { notifyError } from "services/notifications";
-import { DiceResult, GameState, PlayerChoice } from "types";
+import { chooseColumns, endRun, getHint, rollDice } from "services/ipc";
+import {
+  notify,
+  notifyError,
+  notifySuccess,
+  notifyWarning,
+} from "services/notifications";
+import { DiceResult, Feedback, GameState, PlayerChoice, Unlock } from "types";
 import DiceContainer from "./rolling/dice";
 import ChoiceContainer from "./rolling/choice";
 import TurnStartContainer from "./rolling/turnStart";
 import { useTour } from "@reactour/tour";
-import { MdQuestionMark } from "react-icons/md";
+import { MdLightbulbOutline, MdQuestionMark } from "react-icons/md";
 import { useAiTurn } from "hooks/useAiTurn";
 
+/** Show the grade of a human's decision, in training mode. */
+const showFeedback = (feedback?: Feedback) => {
+  if (!feedback) return;
+  const message = `${feedback.grade}! ${feedback.explanation}`;
+  switch (feedback.grade) {
+    case "Excellent":
+    case "Fine":
+      notifySuccess(message, "feedback", 5000);
+      break;
+    case "Risky":
+      notifyWarning(message, "feedback", 6000);
+      break;
+    case "Blunder":
+      notifyError(message, "feedback", 6000);
+      break;
+  }
+};
+
+/** Celebrate the achievements unlocked with the run that has just finished. */
+const showUnlocks = (state: GameState, unlocks: Unlock[] = []) => {
+  unlocks.forEach((unlock) => {
+    const name = state.settings.players[unlock.player]?.name;
+    notifySuccess(
+      `🏆 ${name} unlocked ${unlock.title}! ${unlock.description}`,
+      `achievement-${unlock.achievement}`,
+      6000
+    );
+  });
+};
+
 type RollerProps = {
   setGameState: React.Dispatch<React.SetStateAction<GameState | undefined>>;
   gameState: GameState;
@@ -48,6 +83,7 @@ const DiceRoller: React.FC<RollerProps> = ({ setGameState, gameState }) => {
       const state = await chooseColumns(choice);
       setDice({ dice: [], choices: [] });
       if (state) {
+        showFeedback(state.feedback);
         console.log("updating choices");
         setGameState(state);
         if (isTourOpen && currentStep === 3) {
@@ -64,20 +100,34 @@ const DiceRoller: React.FC<RollerProps> = ({ setGameState, gameState }) => {
     async (forced: boolean) => {
       const state = await endRun(forced);
       setDice({ dice: [], choices: [] });
+      showFeedback(state.feedback);
+      showUnlocks(state, state.unlocked);
       setGameState(state);
     },
     [setGameState]
   );
 
+  const showHint = useCallback(async () => {
+    try {
+      // Only rank the choices while there is a roll to choose from.
+      const hint = await getHint(dice.choices.length ? dice : undefined);
+      const best = hint.choices[0];
+      const message = best
+        ? `Take ${best.choice.filter(Boolean).join(" & ")}: ${best.reason}.`
+        : `${hint.hop ? "Hop!" : "Stop!"} ${hint.hop_reason}`;
+      notify(message, "hint", 6000);
+    } catch (e) {
+      notifyError(`Couldn't get a hint: ${e}`, "hintError");
+    }
+  }, [dice]);
+
   // --- Use the AI Hook ---
   const { aiAction, aiTargetChoice } = useAiTurn({
     player,
-    dice,
     gameState,
     isTourOpen,
-    updateDice,
-    makeChoice,
-    endPlayerRun,
+    setDice,
+    setGameState,
   });
 
   return (
@@ -102,6 +152,15 @@ const DiceRoller: React.FC<RollerProps> = ({ setGameState, gameState }) => {
         aiAction={aiAction}
         aiTargetChoice={aiTargetChoice}
       />
+      {player.mode === "Human" && gameState.winner === null && (
+        <button
+          type="button"
+          className="fixed bottom-0 left-2 m-4 p-2 h-12 border rounded mx-2 btn btn-xl bg-yellow-200 text-black"
+          onClick={showHint}
+        >
+          What would the frog do? <MdLightbulbOutline />
+        </button>
+      )}
       {showTutorial && player.mode === "Human" && (
         <button
           type="button"
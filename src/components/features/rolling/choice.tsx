@@ -56,10 +56,15 @@ const ChoiceContainer: React.FC<{
             aiTargetChoice &&
             choice[0] === aiTargetChoice[0] &&
             choice[1] === aiTargetChoice[1];
+          // Croak chance on the next roll, if this choice is taken.
+          const preview = dice.previews?.find(
+            ({ choice: [first, second] }) =>
+              first === choice[0] && (second ?? null) === (choice[1] ?? null)
+          );
           return (
             <motion.button
               key={index} // Key on the motion component
-              className="btn btn-outline btn-primary btn-lg shadow justify-center text-2xl font-bold h-16 min-w-16 disabled:opacity-80"
+              className="btn btn-outline btn-primary btn-lg shadow flex-col justify-center text-2xl font-bold h-16 min-w-16 disabled:opacity-80"
               style={{
                 borderColor: PlayerColors[playerIndex], // Base border color
                 color: PlayerColors[playerIndex],
@@ -74,7 +79,17 @@ const ChoiceContainer: React.FC<{
               animate="visible"
               variants={choicesVariants}
             >
-              {choice[0]} {choice[1] ? `& ${choice[1]}` : ""}
+              <span>
+                {choice[0]} {choice[1] ? `& ${choice[1]}` : ""}
+              </span>
+              {preview && mode === "Human" && (
+                <span
+                  className="text-xs font-normal"
+                  title="Chance of croaking on the next roll"
+                >
+                  {Math.round(preview.croak_chance * 100)}% croak
+                </span>
+              )}
             </motion.button>
           );
         })
@@ -6,6 +6,7 @@ import { GameState } from "types";
 import GameOverModal from "./Game/gameover";
 import TopBar from "./Game/topbar";
 import GameBoard from "./Game/board";
+import FrogRace from "./Game/race";
 
 export function GamePage() {
   const [gameState, setGameState] = useState<GameState>();
@@ -40,6 +41,7 @@ export function GamePage() {
             playerIndex={gameState.current_player}
           />
           <div className="w-screen flex items-center justify-center flex-col py-3 px-5 mt-7">
+            <FrogRace gameState={gameState} />
             <GameBoard gameState={gameState} />
           </div>
           {gameState.winner === null && (
@@ -1,6 +1,25 @@
 import { useEffect, useState } from "react";
-import { getGameStatistics, startGame, stopGame } from "services/ipc";
-import { GameState, PlayerColors, StatsSummary } from "types";
+import {
+  getGameReview,
+  getGameStatistics,
+  startGame,
+  stopGame,
+} from "services/ipc";
+import {
+  Action,
+  GameState,
+  PlayerColors,
+  PlayerReview,
+  StatsSummary,
+} from "types";
+import { WinTimeline } from "./race";
+
+/** Describe a reviewed decision, e.g. "Stop" or "6 & 8". */
+const describeAction = (action: Action) => {
+  if (typeof action === "string") return action;
+  const [first, second] = action.Choose;
+  return second ? `${first} & ${second}` : `${first}`;
+};
 
 type GameOverModalProps = {
   gameState: GameState;
@@ -13,11 +32,16 @@ const GameOverModal: React.FC<GameOverModalProps> = ({ gameState }) => {
   const winnerName = gameState.winner.name;
   const winnerColor = PlayerColors[gameState.winner.id - 1];
   const [stats, setStats] = useState<StatsSummary>();
+  const [reviews, setReviews] = useState<PlayerReview[]>();
 
   useEffect(() => {
     getGameStatistics().then((stats) => {
       setStats(stats);
     });
+    // The review solves every run, so it follows on after the stats.
+    getGameReview().then((reviews) => {
+      setReviews(reviews);
+    });
   }, []);
 
   return (
@@ -47,37 +71,69 @@ const GameOverModal: React.FC<GameOverModalProps> = ({ gameState }) => {
               <div className="stat-value">{stats?.total_turns ?? "..."}</div>
             </div>
           </div>
+          {stats && stats.win_timeline.length > 1 && (
+            <div className="w-full">
+              <h3>Chance of Winning</h3>
+              <WinTimeline timeline={stats.win_timeline} />
+            </div>
+          )}
           <div className="w-full">
-            {stats?.player_stats.map((playerStat, index) => (
-              <>
-                <h3>
-                  <span
-                    style={{ color: PlayerColors[index], fontWeight: "bold" }}
-                  >
-                    {gameState.settings.players[index].name}
-                  </span>
-                </h3>
-                <table className="table table-zebra w-full">
-                  <thead>
-                    <tr></tr>
-                  </thead>
-                  <tbody>
-                    <tr>
-                      <td>Longest Run</td> <td>{playerStat.longest_run}</td>
-                    </tr>
-                    <tr>
-                      <td>Croaked</td> <td>{playerStat.croaked}</td>
-                    </tr>
-                    <tr>
-                      <td>Banked</td> <td>{playerStat.banked}</td>
-                    </tr>
-                    <tr>
-                      <td>Luck</td> <td>{playerStat.luck.toFixed(2)}</td>
-                    </tr>
-                  </tbody>
-                </table>
-              </>
-            ))}
+            {stats?.player_stats.map((playerStat, index) => {
+              const review = reviews?.[index];
+              return (
+                <>
+                  <h3>
+                    <span
+                      style={{ color: PlayerColors[index], fontWeight: "bold" }}
+                    >
+                      {gameState.settings.players[index].name}
+                    </span>
+                  </h3>
+                  <table className="table table-zebra w-full">
+                    <thead>
+                      <tr></tr>
+                    </thead>
+                    <tbody>
+                      <tr>
+                        <td>Longest Run</td> <td>{playerStat.longest_run}</td>
+                      </tr>
+                      <tr>
+                        <td>Croaked</td> <td>{playerStat.croaked}</td>
+                      </tr>
+                      <tr>
+                        <td>Banked</td> <td>{playerStat.banked}</td>
+                      </tr>
+                      <tr>
+                        <td>Luck</td> <td>{playerStat.luck.toFixed(2)}</td>
+                      </tr>
+                      <tr>
+                        <td>Mistakes</td>{" "}
+                        <td>
+                          {review
+                            ? `${review.mistakes} of ${review.decisions}`
+                            : "..."}
+                        </td>
+                      </tr>
+                      <tr>
+                        <td>Equity Lost</td>{" "}
+                        <td>{review?.equity_lost.toFixed(2) ?? "..."}</td>
+                      </tr>
+                      {review?.blunders.map((blunder, idx) => (
+                        <tr key={idx}>
+                          <td>Blunder {idx + 1}</td>
+                          <td>
+                            Run {blunder.run + 1}, roll {blunder.turn + 1}:{" "}
+                            {describeAction(blunder.played)} instead of{" "}
+                            {describeAction(blunder.best)} (-
+                            {blunder.equity_lost.toFixed(2)})
+                          </td>
+                        </tr>
+                      ))}
+                    </tbody>
+                  </table>
+                </>
+              );
+            })}
           </div>
         </div>
         <div className="modal-action flex justify-center">
@@ -0,0 +1,68 @@
+import { useEffect, useState } from "react";
+import { getWinChances } from "services/ipc";
+import { GameState, PlayerColors } from "types";
+
+/** Each player's chance of winning, as a bar split between the frogs. */
+const FrogRace = ({ gameState }: { gameState: GameState }) => {
+  const [chances, setChances] = useState<number[]>();
+
+  useEffect(() => {
+    // Only worth re-estimating once a run has been banked or croaked.
+    getWinChances()
+      .then((estimate) => setChances(estimate.chances.map((c) => c.value)))
+      .catch((e) => console.error("Couldn't estimate win chances", e));
+  }, [gameState.current_player, gameState.winner]);
+
+  if (!chances) return null;
+  return (
+    <div className="w-3/4 flex h-3 rounded overflow-hidden mb-3">
+      {chances.map((chance, index) => (
+        <div
+          key={index}
+          className="h-full transition-all duration-500"
+          style={{
+            width: `${chance * 100}%`,
+            backgroundColor: PlayerColors[index],
+          }}
+          title={`${gameState.settings.players[index].name}: ${(
+            chance * 100
+          ).toFixed(0)}%`}
+        />
+      ))}
+    </div>
+  );
+};
+
+/** Each player's chance of winning over the game, one line per player. */
+export const WinTimeline = ({ timeline }: { timeline: number[][] }) => {
+  if (timeline.length < 2) return null;
+  const width = 300;
+  const height = 100;
+  const x = (run: number) => (run / (timeline.length - 1)) * width;
+  const y = (chance: number) => height - chance * height;
+  // runs whose chances couldn't be estimated are left out
+  const players = timeline.find((chances) => chances.length > 0) ?? [];
+  return (
+    <svg
+      className="w-full"
+      viewBox={`0 0 ${width} ${height}`}
+      preserveAspectRatio="none"
+    >
+      {players.map((_, player) => (
+        <polyline
+          key={player}
+          fill="none"
+          stroke={PlayerColors[player]}
+          strokeWidth={2}
+          points={timeline
+            .flatMap((chances, run) =>
+              chances.length > 0 ? [`${x(run)},${y(chances[player])}`] : [],
+            )
+            .join(" ")}
+        />
+      ))}
+    </svg>
+  );
+};
+
+export default FrogRace;
@@ -0,0 +1,147 @@
+import { useEffect, useState } from "react";
+import { MdDelete } from "react-icons/md";
+import {
+  createProfile,
+  deleteProfile,
+  getProfiles,
+  updateProfile,
+} from "services/ipc";
+import { notifyError } from "services/notifications";
+import { PlayerMode, Profile, ProfileDetails, Profiles } from "types";
+
+const MODES: PlayerMode[] = ["Human", "Safe", "Normal", "Risky", "Adaptive"];
+
+const blankProfile: ProfileDetails = {
+  name: "",
+  colour: "#99f2e6",
+  avatar: "🐸",
+  preferred_mode: "Human",
+};
+
+/** Regular players, kept across games. */
+export function ProfilesPage() {
+  const [profiles, setProfiles] = useState<Profiles>({});
+  const [details, setDetails] = useState<ProfileDetails>(blankProfile);
+
+  useEffect(() => {
+    getProfiles()
+      .then(setProfiles)
+      .catch((e) => notifyError(`Couldn't load profiles: ${e}`, "ProfileError"));
+  }, []);
+
+  const create = async () => {
+    try {
+      const profile = await createProfile(details);
+      setProfiles({ ...profiles, [profile.id]: profile });
+      setDetails(blankProfile);
+    } catch (e) {
+      notifyError(`Couldn't create the profile: ${e}`, "ProfileError");
+    }
+  };
+
+  const change = async (profile: Profile, changes: Partial<ProfileDetails>) => {
+    try {
+      const updated = await updateProfile(profile.id, {
+        ...profile,
+        ...changes,
+      });
+      setProfiles({ ...profiles, [updated.id]: updated });
+    } catch (e) {
+      notifyError(`Couldn't update the profile: ${e}`, "ProfileError");
+    }
+  };
+
+  const remove = async (profile: Profile) => {
+    if (!window.confirm(`Delete ${profile.name}'s profile?`)) return;
+    try {
+      setProfiles(await deleteProfile(profile.id));
+    } catch (e) {
+      notifyError(`Couldn't delete the profile: ${e}`, "ProfileError");
+    }
+  };
+
+  return (
+    <div className="flex flex-col items-center p-6">
+      <h1 className="text-2xl font-bold mb-4">Players</h1>
+      <table className="table table-zebra w-full">
+        <tbody>
+          {Object.values(profiles).map((profile) => (
+            <tr key={profile.id}>
+              <td>
+                <input
+                  className="input input-sm w-16"
+                  defaultValue={profile.avatar}
+                  onBlur={(e) => change(profile, { avatar: e.target.value })}
+                />
+              </td>
+              <td style={{ color: profile.colour }} className="font-bold">
+                {profile.name}
+              </td>
+              <td>
+                <input
+                  type="color"
+                  value={profile.colour ?? "#ffffff"}
+                  onChange={(e) => change(profile, { colour: e.target.value })}
+                />
+              </td>
+              <td>
+                <select
+                  className="select select-sm"
+                  value={
+                    typeof profile.preferred_mode === "string"
+                      ? profile.preferred_mode
+                      : "Human"
+                  }
+                  onChange={(e) =>
+                    change(profile, {
+                      preferred_mode: e.target.value as PlayerMode,
+                    })
+                  }
+                >
+                  {MODES.map((mode) => (
+                    <option key={mode}>{mode}</option>
+                  ))}
+                </select>
+              </td>
+              <td>{new Date(profile.created * 1000).toLocaleDateString()}</td>
+              <td>
+                <button
+                  className="btn btn-sm"
+                  onClick={() => remove(profile)}
+                  title="Delete"
+                >
+                  <MdDelete />
+                </button>
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      <div className="flex flex-row items-center gap-2 mt-6">
+        <input
+          className="input w-16"
+          value={details.avatar}
+          onChange={(e) => setDetails({ ...details, avatar: e.target.value })}
+        />
+        <input
+          className="input"
+          placeholder="Name"
+          value={details.name}
+          onChange={(e) => setDetails({ ...details, name: e.target.value })}
+        />
+        <input
+          type="color"
+          value={details.colour}
+          onChange={(e) => setDetails({ ...details, colour: e.target.value })}
+        />
+        <button
+          className="btn bg-green-400 text-black"
+          disabled={!details.name.trim()}
+          onClick={create}
+        >
+          Add Player
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,176 @@
+import { useEffect, useState } from "react";
+import {
+  MdContentCopy,
+  MdContentPaste,
+  MdDelete,
+  MdEdit,
+  MdFileDownload,
+  MdFileUpload,
+  MdPlayArrow,
+} from "react-icons/md";
+import {
+  deleteSave,
+  exportGameRecord,
+  getGameNotation,
+  importGameNotation,
+  importGameRecord,
+  listSaves,
+  loadSave,
+  renameSave,
+} from "services/ipc";
+import { notifyError, notifySuccess } from "services/notifications";
+import { SaveSummary } from "types";
+
+/** Every saved game, to carry on, rename or delete. */
+export function SavesPage() {
+  const [saves, setSaves] = useState<SaveSummary[]>([]);
+
+  useEffect(() => {
+    listSaves()
+      .then(setSaves)
+      .catch((e) => notifyError(`Couldn't list saved games: ${e}`, "SaveError"));
+  }, []);
+
+  const play = async (id: string) => {
+    try {
+      await loadSave(id);
+      window.location.href = "/game";
+    } catch (e) {
+      notifyError(`Couldn't load the game: ${e}`, "SaveError");
+    }
+  };
+
+  const rename = async (save: SaveSummary) => {
+    const name = window.prompt("Rename the game", save.name);
+    if (!name) return;
+    try {
+      setSaves(await renameSave(save.id, name));
+    } catch (e) {
+      notifyError(`Couldn't rename the game: ${e}`, "SaveError");
+    }
+  };
+
+  const remove = async (save: SaveSummary) => {
+    if (!window.confirm(`Delete "${save.name}"?`)) return;
+    try {
+      setSaves(await deleteSave(save.id));
+    } catch (e) {
+      notifyError(`Couldn't delete the game: ${e}`, "SaveError");
+    }
+  };
+
+  const exportRecord = async (save: SaveSummary) => {
+    const path = window.prompt(
+      "Save the game record as",
+      `${save.name}.canthop.json`
+    );
+    if (!path) return;
+    try {
+      const written = await exportGameRecord(save.id, path);
+      notifySuccess(`Game record saved to ${written}`, "SaveExport", 5000);
+    } catch (e) {
+      notifyError(`Couldn't export the game: ${e}`, "SaveError");
+    }
+  };
+
+  const importRecord = async () => {
+    const path = window.prompt("Game record to open");
+    if (!path) return;
+    try {
+      setSaves(await importGameRecord(path));
+    } catch (e) {
+      notifyError(`Couldn't import the game: ${e}`, "SaveError", 10000);
+    }
+  };
+
+  const copyNotation = async (save: SaveSummary) => {
+    try {
+      await navigator.clipboard.writeText(await getGameNotation(save.id));
+      notifySuccess("Game notation copied", "SaveExport");
+    } catch (e) {
+      notifyError(`Couldn't copy the game: ${e}`, "SaveError");
+    }
+  };
+
+  const pasteNotation = async () => {
+    try {
+      const notation = await navigator.clipboard.readText();
+      setSaves(await importGameNotation(notation));
+    } catch (e) {
+      notifyError(`Couldn't import the game: ${e}`, "SaveError", 10000);
+    }
+  };
+
+  return (
+    <div className="flex flex-col items-center p-6">
+      <h1 className="text-2xl font-bold mb-4">Saved Games</h1>
+      {saves.length === 0 && <p>No saved games yet.</p>}
+      <table className="table table-zebra w-full">
+        <tbody>
+          {saves.map((save) => (
+            <tr key={save.id}>
+              <td>
+                <div className="font-bold">{save.name}</div>
+                <div className="text-sm opacity-70">
+                  {save.players.map((player) => player.name).join(", ")}
+                </div>
+              </td>
+              <td>
+                {save.winner
+                  ? `${save.winner} won`
+                  : `${save.runs} runs played`}
+              </td>
+              <td>{new Date(save.updated * 1000).toLocaleString()}</td>
+              <td className="flex gap-2">
+                <button
+                  className="btn btn-sm"
+                  disabled={save.winner !== null}
+                  onClick={() => play(save.id)}
+                  title="Carry on"
+                >
+                  <MdPlayArrow />
+                </button>
+                <button
+                  className="btn btn-sm"
+                  onClick={() => rename(save)}
+                  title="Rename"
+                >
+                  <MdEdit />
+                </button>
+                <button
+                  className="btn btn-sm"
+                  onClick={() => exportRecord(save)}
+                  title="Export"
+                >
+                  <MdFileDownload />
+                </button>
+                <button
+                  className="btn btn-sm"
+                  onClick={() => copyNotation(save)}
+                  title="Copy notation"
+                >
+                  <MdContentCopy />
+                </button>
+                <button
+                  className="btn btn-sm"
+                  onClick={() => remove(save)}
+                  title="Delete"
+                >
+                  <MdDelete />
+                </button>
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      <div className="flex gap-2 mt-6">
+        <button className="btn" onClick={importRecord}>
+          <MdFileUpload /> Import Game Record
+        </button>
+        <button className="btn" onClick={pasteNotation}>
+          <MdContentPaste /> Paste Notation
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -1,9 +1,198 @@
+import { useEffect, useState } from "react";
+import {
+  getAchievements,
+  getCareerStats,
+  getLeaderboard,
+  getUnlockedAchievements,
+  recommendBots,
+} from "services/ipc";
+import { notifyError } from "services/notifications";
+import {
+  AchievementInfo,
+  Achievements,
+  BotMatch,
+  CareerGame,
+  CareerStats,
+  Standing,
+} from "types";
+
+const percent = (rate: number) => `${(rate * 100).toFixed(0)}%`;
+
+/** Croak rate and luck over a player's games, oldest first. */
+const CareerChart = ({ games }: { games: CareerGame[] }) => {
+  if (games.length < 2) return null;
+  const width = 300;
+  const height = 100;
+  const x = (game: number) => (game / (games.length - 1)) * width;
+  const line = (value: (game: CareerGame) => number) =>
+    games
+      .map((game, index) => `${x(index)},${height - value(game) * height}`)
+      .join(" ");
+  return (
+    <svg
+      className="w-full"
+      viewBox={`0 0 ${width} ${height}`}
+      preserveAspectRatio="none"
+    >
+      <polyline
+        fill="none"
+        stroke="#f87171"
+        strokeWidth={2}
+        points={line((game) => game.croak_rate)}
+      />
+      <polyline
+        fill="none"
+        stroke="#99f2e6"
+        strokeWidth={2}
+        // luck is centred on 0, so shift it to the middle of the chart
+        points={line((game) => 0.5 + game.luck)}
+      />
+    </svg>
+  );
+};
+
+type CareerProps = {
+  id: string;
+  career: CareerStats;
+  /** Achievements the player has unlocked */
+  achievements: AchievementInfo[];
+};
+
+const Career = ({ id, career, achievements }: CareerProps) => {
+  const [fairest, setFairest] = useState<BotMatch>();
+
+  useEffect(() => {
+    recommendBots(id)
+      .then((matches) => setFairest(matches[0]))
+      .catch((e) => console.error("Couldn't recommend a bot", e));
+  }, [id]);
+
+  return (
+    <div className="w-full mb-8">
+      <h2 className="text-xl font-bold">{career.name}</h2>
+      <table className="table table-zebra w-full">
+        <tbody>
+          <tr>
+            <td>Games Won</td>
+            <td>
+              {career.games_won} / {career.games_played} (
+              {percent(career.games_won / career.games_played)})
+            </td>
+          </tr>
+          {Object.entries(career.vs_bots).map(([mode, record]) => (
+            <tr key={mode}>
+              <td>Against {mode} Bots</td>
+              <td>
+                {record.won} / {record.played} (
+                {percent(record.won / record.played)})
+              </td>
+            </tr>
+          ))}
+          <tr>
+            <td>Croak Rate</td>
+            <td>{percent(career.croak_rate)}</td>
+          </tr>
+          <tr>
+            <td>Luck</td>
+            <td>{career.luck.toFixed(3)}</td>
+          </tr>
+          {fairest && (
+            <tr>
+              <td>Fairest Bot</td>
+              <td>
+                {typeof fairest.mode === "string" ? fairest.mode : "Custom"} (
+                {percent(fairest.expected)} expected)
+              </td>
+            </tr>
+          )}
+          <tr>
+            <td>Favourite Columns</td>
+            <td>
+              {career.favourite_columns.map(([column]) => column).join(", ")}
+            </td>
+          </tr>
+          <tr>
+            <td>Achievements</td>
+            <td>
+              {achievements.map((info) => (
+                <span
+                  key={info.achievement}
+                  className="badge badge-success mr-1"
+                  title={info.description}
+                >
+                  🏆 {info.title}
+                </span>
+              ))}
+            </td>
+          </tr>
+        </tbody>
+      </table>
+      <CareerChart games={career.games} />
+    </div>
+  );
+};
+
+/** Every rated player and bot, highest rated first. */
+const Leaderboard = ({ standings }: { standings: Standing[] }) => (
+  <table className="table table-zebra w-full mb-8">
+    <thead>
+      <tr>
+        <th>#</th>
+        <th>Player</th>
+        <th>Rating</th>
+        <th>Games</th>
+      </tr>
+    </thead>
+    <tbody>
+      {standings.map((standing, index) => (
+        <tr key={standing.key} className={standing.bot ? "opacity-70" : ""}>
+          <td>{index + 1}</td>
+          <td>{standing.name}</td>
+          <td>{standing.rating.toFixed(0)}</td>
+          <td>{standing.games}</td>
+        </tr>
+      ))}
+    </tbody>
+  </table>
+);
+
+/** Every player's statistics over all the games they've finished. */
 export function StatsPage() {
+  const [careers, setCareers] = useState<Record<string, CareerStats>>({});
+  const [standings, setStandings] = useState<Standing[]>([]);
+  const [achievements, setAchievements] = useState<AchievementInfo[]>([]);
+  const [unlocked, setUnlocked] = useState<Achievements>({});
 
-    return (
-        <div>
-            <h1 className="">Statistics</h1>
+  useEffect(() => {
+    getCareerStats()
+      .then(setCareers)
+      .catch((e) => notifyError(`Couldn't load statistics: ${e}`, "StatsError"));
+    getLeaderboard()
+      .then(setStandings)
+      .catch((e) => notifyError(`Couldn't load ratings: ${e}`, "StatsError"));
+    Promise.all([getAchievements(), getUnlockedAchievements()])
+      .then(([all, unlocked]) => {
+        setAchievements(all);
+        setUnlocked(unlocked);
+      })
+      .catch((e) => console.error("Couldn't load achievements", e));
+  }, []);
 
-        </div>
-    );
-}
\ No newline at end of file
+  return (
+    <div className="flex flex-col items-center p-6">
+      <h1 className="text-2xl font-bold mb-4">Statistics</h1>
+      {standings.length > 0 && <Leaderboard standings={standings} />}
+      {Object.keys(careers).length === 0 && <p>No finished games yet.</p>}
+      {Object.entries(careers).map(([key, career]) => (
+        <Career
+          key={key}
+          id={key}
+          career={career}
+          achievements={achievements.filter(
+            (info) => unlocked[key]?.[info.achievement] !== undefined
+          )}
+        />
+      ))}
+    </div>
+  );
+}
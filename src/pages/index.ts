@@ -1,4 +1,6 @@
 export * from "./Game";
+export * from "./Profiles";
+export * from "./Saves";
 export * from "./Stats";
 export * from "./Settings";
 export * from "./Splash";
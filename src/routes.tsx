@@ -1,7 +1,20 @@
 import { Routes, Route, useLocation } from "react-router-dom";
-import { SplashPage, GamePage, StatsPage, SettingsPage } from "pages";
+import {
+  SplashPage,
+  GamePage,
+  ProfilesPage,
+  SavesPage,
+  StatsPage,
+  SettingsPage,
+} from "pages";
 import { checkEnv } from "utils";
-import { MdDataExploration, MdGames, MdSettings } from "react-icons/md";
+import {
+  MdDataExploration,
+  MdGames,
+  MdPeople,
+  MdSave,
+  MdSettings,
+} from "react-icons/md";
 import { AnimatePresence, motion } from "motion/react";
 
 type RouteType = {
@@ -28,6 +41,18 @@ const allRoutes: RouteType[] = [
     element: <SettingsPage />,
     icon: <MdSettings />,
   },
+  {
+    title: "Players",
+    path: "/players",
+    element: <ProfilesPage />,
+    icon: <MdPeople />,
+  },
+  {
+    title: "Saved Games",
+    path: "/saves",
+    element: <SavesPage />,
+    icon: <MdSave />,
+  },
   {
     title: "Splash",
     path: "/",
@@ -1,10 +1,33 @@
 import { invoke } from "@tauri-apps/api/core";
 import {
+  AchievementInfo,
+  Achievements,
+  BotMatch,
+  CareerStats,
+  ChoiceRationale,
   DiceResult,
   GameState,
+  Hint,
+  HopRationale,
+  Objective,
+  OpponentModel,
+  OptimalPlay,
+  Personalities,
+  Personality,
   PlayerChoice,
+  PlayerReview,
+  Profile,
+  ProfileDetails,
+  Profiles,
+  ReplayFrame,
+  ReplayPosition,
+  RollAnalysis,
+  RunOutlook,
+  SaveSummary,
   SettingsState,
+  Standing,
   StatsSummary,
+  WinChances,
 } from "types";
 import { notifyError } from "./notifications";
 
@@ -12,18 +35,23 @@ export async function initStore(): Promise<void> {
   try {
     await invoke("init_store");
   } catch (e) {
-    notifyError(`Failed to initialize game storage: ${e}`, "StoreError");
+    // Stays up longer, as it may mean a saved game had to be set aside.
+    notifyError(`Failed to initialize game storage: ${e}`, "StoreError", 10000);
   }
 }
 
 /**
- * Starts the game with the given settings.
+ * Starts the game with the given settings, in a save slot of its own.
  * @param settings - The settings to start the game with.
+ * @param name - Optional name for the saved game, otherwise named after the players.
  * @throws Will throw an error if the game fails to start.
  */
-export async function startGame(settings: SettingsState): Promise<boolean> {
+export async function startGame(
+  settings: SettingsState,
+  name?: string
+): Promise<boolean> {
   try {
-    await invoke("start_game", { settings });
+    await invoke("start_game", { settings, name });
     return true;
   } catch (e) {
     notifyError(`Failed to start game: ${e}`, "StartError");
@@ -89,6 +117,46 @@ export async function getGameStatistics(): Promise<StatsSummary> {
   return await invoke<StatsSummary>("get_game_statistics");
 }
 
+/** Review each player's decisions against the solver's, which takes a few seconds. */
+export async function getGameReview(): Promise<PlayerReview[]> {
+  return await invoke<PlayerReview[]>("get_game_review");
+}
+
+/** Return the odds of the current player's next roll, and which dice would croak them. */
+export async function getRollAnalysis(): Promise<RollAnalysis> {
+  return await invoke<RollAnalysis>("get_roll_analysis");
+}
+
+/** Return what the current player can expect from rolling up to `rolls` more times. */
+export async function getRunOutlook(rolls: number): Promise<RunOutlook> {
+  return await invoke<RunOutlook>("get_run_outlook", { rolls });
+}
+
+/**
+ * Return how the current player should play the rest of their run for the objective.
+ * Pass the current roll, if any, to have its choices ranked.
+ */
+export async function getOptimalPlay(
+  objective: Objective,
+  options?: DiceResult
+): Promise<OptimalPlay> {
+  return await invoke<OptimalPlay>("get_optimal_play", { objective, options });
+}
+
+/** Estimate each player's chance of winning from here, by playing the game out.
+ * @param playouts - Optional number of games to play out, more is slower but steadier.
+ */
+export async function getWinChances(playouts?: number): Promise<WinChances> {
+  return await invoke<WinChances>("get_win_chances", { playouts });
+}
+
+/** Return what the bots have learnt about each human, by name. */
+export async function getOpponentModels(): Promise<
+  Record<string, OpponentModel>
+> {
+  return await invoke<Record<string, OpponentModel>>("get_opponent_models");
+}
+
 /** Generate a random name, can be seeded for reproducibility.
  * @param seed - Optional seed for random name generation.
  * @returns A promise that resolves to the generated name.
@@ -97,13 +165,189 @@ export async function getName(seed?: number): Promise<string> {
   return await invoke<string>("get_name", { seed });
 }
 
+/**
+ * Play the current bot's whole run in the backend.
+ * Each step is emitted as a `bot-step` event, and repeat calls are ignored.
+ */
+export async function playBotTurn(): Promise<void> {
+  await invoke("play_bot_turn");
+}
+
 /** Check if the AI player should hop or stop. */
 export async function aiCheckContinue(): Promise<boolean> {
   return await invoke<boolean>("check_continue");
 }
 
+/** Check if the AI player should hop or stop, and why. */
+export async function aiExplainContinue(): Promise<{
+  decision: boolean;
+  rationale: HopRationale;
+}> {
+  return await invoke("check_continue", { explain: true });
+}
+
 export async function aiChooseColumn(
   options: DiceResult
 ): Promise<PlayerChoice> {
   return await invoke<PlayerChoice>("choose_column", { options });
 }
+
+/** Choose the AI player's columns, and explain the choice. */
+export async function aiExplainChoice(options: DiceResult): Promise<{
+  decision: PlayerChoice;
+  rationale: ChoiceRationale;
+}> {
+  return await invoke("choose_column", { options, explain: true });
+}
+
+/**
+ * Ask what the frog would do in the current human player's position.
+ * Pass the current roll, if any, to have its choices ranked.
+ */
+export async function getHint(options?: DiceResult): Promise<Hint> {
+  return await invoke<Hint>("get_hint", { options });
+}
+
+/** Return all saved bot personalities, by name. */
+export async function getPersonalities(): Promise<Personalities> {
+  return await invoke<Personalities>("get_personalities");
+}
+
+/** Save a named bot personality, replacing any existing one with that name. */
+export async function savePersonality(
+  name: string,
+  personality: Personality
+): Promise<Personalities | undefined> {
+  try {
+    return await invoke<Personalities>("save_personality", {
+      name,
+      personality,
+    });
+  } catch (e) {
+    notifyError(`Failed to save personality: ${e}`, "PersonalityError");
+  }
+}
+
+/** Remove a named bot personality. */
+export async function deletePersonality(
+  name: string
+): Promise<Personalities | undefined> {
+  try {
+    return await invoke<Personalities>("delete_personality", { name });
+  } catch (e) {
+    notifyError(`Failed to delete personality: ${e}`, "PersonalityError");
+  }
+}
+
+/** Return a summary of every saved game, most recently played first. */
+export async function listSaves(): Promise<SaveSummary[]> {
+  return await invoke<SaveSummary[]>("list_saves");
+}
+
+/** Carry on playing a saved game, keeping the one being played in its own slot. */
+export async function loadSave(id: string): Promise<GameState> {
+  return await invoke<GameState>("load_save", { id });
+}
+
+/** Give a saved game a new name. */
+export async function renameSave(
+  id: string,
+  name: string
+): Promise<SaveSummary[]> {
+  return await invoke<SaveSummary[]>("rename_save", { id, name });
+}
+
+/** Delete a saved game, which can't be the one being played. */
+export async function deleteSave(id: string): Promise<SaveSummary[]> {
+  return await invoke<SaveSummary[]>("delete_save", { id });
+}
+
+/** Return every player profile, by id. */
+export async function getProfiles(): Promise<Profiles> {
+  return await invoke<Profiles>("get_profiles");
+}
+
+/** Create a player profile, with a new id. */
+export async function createProfile(details: ProfileDetails): Promise<Profile> {
+  return await invoke<Profile>("create_profile", { details });
+}
+
+/** Change a player profile's details, keeping its id. */
+export async function updateProfile(
+  id: string,
+  details: ProfileDetails
+): Promise<Profile> {
+  return await invoke<Profile>("update_profile", { id, details });
+}
+
+/** Delete a player profile. */
+export async function deleteProfile(id: string): Promise<Profiles> {
+  return await invoke<Profiles>("delete_profile", { id });
+}
+
+/** Return every player's statistics over all their finished games, by profile id or name. */
+export async function getCareerStats(): Promise<Record<string, CareerStats>> {
+  return await invoke<Record<string, CareerStats>>("get_career_stats");
+}
+
+/** Return the standings of every rated player and bot, highest rated first. */
+export async function getLeaderboard(): Promise<Standing[]> {
+  return await invoke<Standing[]>("get_leaderboard");
+}
+
+/** Return the bots to play against a player (profile id or name), fairest first. */
+export async function recommendBots(player: string): Promise<BotMatch[]> {
+  return await invoke<BotMatch[]>("recommend_bots", { player });
+}
+
+/** Return every achievement, with what it's for. */
+export async function getAchievements(): Promise<AchievementInfo[]> {
+  return await invoke<AchievementInfo[]>("get_achievements");
+}
+
+/** Return the achievements each player has unlocked, by profile id or name. */
+export async function getUnlockedAchievements(): Promise<Achievements> {
+  return await invoke<Achievements>("get_unlocked_achievements");
+}
+
+/** Write a saved game to a game record file, returning where it was written.
+ * Relative paths are from the user's documents folder. */
+export async function exportGameRecord(
+  id: string,
+  path: string
+): Promise<string> {
+  return await invoke<string>("export_game_record", { id, path });
+}
+
+/** Check a game record file follows the rules, and keep it as a saved game. */
+export async function importGameRecord(path: string): Promise<SaveSummary[]> {
+  return await invoke<SaveSummary[]>("import_game_record", { path });
+}
+
+/** Return a saved game written in Can't Hop notation. */
+export async function getGameNotation(id: string): Promise<string> {
+  return await invoke<string>("get_game_notation", { id });
+}
+
+/** Check a game written in Can't Hop notation follows the rules, and keep it as a saved game. */
+export async function importGameNotation(
+  notation: string
+): Promise<SaveSummary[]> {
+  return await invoke<SaveSummary[]>("import_game_notation", { notation });
+}
+
+/** Show a saved game as it was at a point in its replay. */
+export async function getReplayFrame(
+  id: string,
+  position: ReplayPosition
+): Promise<ReplayFrame> {
+  return await invoke<ReplayFrame>("get_replay_frame", { id, position });
+}
+
+/** Carry on a saved game from a point in its replay, as a new saved game. */
+export async function practiseFromPosition(
+  id: string,
+  position: ReplayPosition
+): Promise<GameState> {
+  return await invoke<GameState>("practise_from_position", { id, position });
+}
@@ -8,9 +8,64 @@ export type Player = {
   name: string;
   /** Number of columns the player has won so far */
   won_cols: number[];
+  /** Id of the profile the player is playing as, if any */
+  profile?: string;
 };
 
-export type PlayerMode = "Human" | "Safe" | "Normal" | "Risky";
+/** Details of a profile that can be chosen when creating or editing it. */
+export type ProfileDetails = {
+  name: string;
+  /** Colour to show the player in, as a CSS colour */
+  colour?: string;
+  /** Avatar to show for the player, an emoji or image name */
+  avatar?: string;
+  /** Mode the player usually plays as */
+  preferred_mode: PlayerMode;
+};
+
+/** Someone who plays regularly, kept across games. */
+export type Profile = ProfileDetails & {
+  /** Stable id, a UUID */
+  id: string;
+  /** When the profile was created, in seconds since the Unix epoch */
+  created: number;
+};
+
+/** Every player profile, by id. */
+export type Profiles = Record<string, Profile>;
+
+export type PlayerMode =
+  | "Human"
+  | "Safe"
+  | "Normal"
+  | "Risky"
+  | "Adaptive"
+  | { Custom: Personality };
+
+/** Tunable parameters that drive how a bot player hops, stops and picks columns */
+export type Personality = {
+  /** Bonus for using an already active column if < 3 are active */
+  weight_active: number;
+  /** Reward for progress already made */
+  weight_progress: number;
+  /** Reward for being closer to the top */
+  weight_distance: number;
+  /** Reward for blocking opponents who are close */
+  weight_opponent: number;
+  /** Reward for racing opponents on the columns they're known to favour */
+  weight_rival: number;
+  /** Reward for columns statistically easier to roll */
+  weight_probability: number;
+  /** Croak chance (0.0 - 1.0) the bot is willing to face at the start of a run */
+  risk_threshold: number;
+  /** How much the threshold decreases per hop made in the current run */
+  risk_aversion_per_hop: number;
+  /** Lowest the threshold can drop to, however long the run */
+  minimum_risk_threshold: number;
+};
+
+/** User created personalities, saved by name */
+export type Personalities = Record<string, Personality>;
 
 export type Column = {
   /** The dice number of the column */
@@ -46,6 +101,8 @@ export type SettingsState = {
   players: Player[];
   /** Number of columns required to win */
   win_cols: number;
+  /** Grade each human's decisions against the solver as they play */
+  training?: boolean;
 };
 
 /** Game state information */
@@ -62,14 +119,142 @@ export type GameState = {
   columns: Columns;
   /** Info of winning player */
   winner: Player | null;
+  /** Grade of the last decision a human made, in training mode */
+  feedback?: Feedback;
+  /** Achievements unlocked with the run that has just finished */
+  unlocked?: Unlock[];
+  /** Humans' recent skill against bots (0.0 - 1.0), which adaptive bots start from */
+  adaptive_skill: number;
+  /** What bots have learnt about each human player, by player index */
+  opponent_models: Record<number, OpponentModel>;
+};
+
+/** What bots have learnt about how a human plays, from their recorded runs */
+export type OpponentModel = {
+  /** Runs the player chose to end */
+  banked: number;
+  /** Runs the player croaked */
+  croaked: number;
+  /** Average croak chance (0.0 - 1.0) the player faced when they chose to stop, which bots pace themselves against */
+  stop_chance: number;
+  /** Times each column has been chosen, by column index */
+  column_picks: number[];
+};
+
+/** Dice that would leave the player with no move, in any order. */
+export type BustRoll = {
+  dice: number[];
+  ways: number;
+};
+
+/** Breakdown of the next roll, from every possible roll of the dice. */
+export type RollAnalysis = {
+  croak_chance: number;
+  /** Chance of each column being offered, by column index (2d6 - 2). */
+  hit_chances: number[];
+  /** Rolls that would croak, most likely first. */
+  bust_rolls: BustRoll[];
+};
+
+/** What to expect from the rest of a run if the player keeps rolling. */
+export type RunOutlook = {
+  /** Chance of surviving 1, 2, 3... more rolls. */
+  survival: number[];
+  /** Expected change in the hops risked on each column, by column index (2d6 - 2). */
+  expected_gain: number[];
+  /** Progress banked by stopping now, in columns' worth of hops. */
+  stop_value: number;
+  /** Expected progress banked by rolling once more, then stopping. */
+  roll_value: number;
+};
+
+/** What a run is played for, when solving for the best play. */
+export type Objective = "Progress" | "ClaimColumn";
+
+/** How to play the rest of a run for the best result. */
+export type OptimalPlay = {
+  objective: Objective;
+  hop: boolean;
+  stop_value: number;
+  roll_value: number;
+  /** Offered choices with the value of taking each, best first. */
+  choices: [PlayerChoice, number][];
+};
+
+/** Odds of the next roll, after taking one of the offered choices. */
+export type ChoicePreview = {
+  choice: [number, number | null];
+  croak_chance: number;
+  /** Chance of each column being offered, by column index (2d6 - 2). */
+  hit_chances: number[];
 };
 
 /** Result of rolling the four dice */
 export type DiceResult = {
   dice: number[];
   choices: [number, number | undefined][];
+  previews?: ChoicePreview[];
+};
+
+/** Why a bot decided to hop or stop. */
+export type HopRationale = {
+  hop: boolean;
+  croak_chance: number;
+  risk_threshold: number;
+  rival_stop_chance: number | null;
+  topped: boolean;
+};
+
+/** Attractiveness of a column to a bot, broken down by factor. */
+export type ColumnScore = {
+  col: number;
+  active: number;
+  progress: number;
+  distance: number;
+  opponent: number;
+  probability: number;
+  rival: number;
+  total: number;
 };
 
+export type ChoiceScore = {
+  choice: PlayerChoice;
+  columns: ColumnScore[];
+  multiplier: number;
+  score: number;
+};
+
+/** Why a bot picked one of the offered choices, with every option ranked best first. */
+export type ChoiceRationale = {
+  choice: PlayerChoice;
+  rank: number;
+  ranked: ChoiceScore[];
+};
+
+/** One of the offered choices, ranked for a hint. */
+export type ChoiceHint = {
+  choice: PlayerChoice;
+  reason: string;
+  score: ChoiceScore;
+};
+
+/** Advice for a human player: what the frog would do in their position. */
+export type Hint = {
+  hop: boolean;
+  hop_reason: string;
+  rationale: HopRationale;
+  choices: ChoiceHint[];
+};
+
+/** A step in a bot's run, streamed from the backend as a `bot-step` event */
+export type BotStep =
+  | { kind: "Decided"; hop: boolean; rationale: HopRationale }
+  | { kind: "Rolled"; dice: DiceResult }
+  | { kind: "Chose"; choice: PlayerChoice; rationale: ChoiceRationale }
+  | { kind: "Moved"; state: GameState }
+  | { kind: "Croaked"; state: GameState }
+  | { kind: "Banked"; state: GameState };
+
 /** Global Definition of the four player colours */
 export const PlayerColors = ["#ffffff", "#f87171", "#99f2e6", "#a78bfa"];
 
@@ -87,10 +272,172 @@ export type PlayerStats = {
   luck: number;
 };
 
+/** Something a player can do during a run. */
+export type Action = "Hop" | "Stop" | { Choose: PlayerChoice };
+
+/** A decision that gave away equity, compared with the solver's. */
+export type Blunder = {
+  /** Run (from 0) the decision was made in */
+  run: number;
+  /** Roll (from 0) within the run */
+  turn: number;
+  played: Action;
+  best: Action;
+  /** Progress given away, in columns' worth of hops */
+  equity_lost: number;
+};
+
+/** How a decision compares with the solver's. */
+export type Grade = "Excellent" | "Fine" | "Risky" | "Blunder";
+
+/** Feedback on a human's decision, for training mode. */
+export type Feedback = {
+  grade: Grade;
+  played: Action;
+  best: Action;
+  /** Progress given away, in columns' worth of hops */
+  equity_lost: number;
+  explanation: string;
+};
+
+/** How well a player made their decisions over a game. */
+export type PlayerReview = {
+  decisions: number;
+  mistakes: number;
+  equity_lost: number;
+  /** Worst decisions, worst first */
+  blunders: Blunder[];
+};
+
 export type StatsSummary = {
   /** Stats of individual players */
   player_stats: PlayerStats[];
   /** Column that had the most total hops, normalized for column height */
   most_contested_column: number;
   total_turns: number;
+  /** Each player's chance of winning at the start and after every run, empty if not estimated */
+  win_timeline: number[][];
+};
+
+/** A measured value with its 95% confidence interval. */
+export type Estimate = {
+  value: number;
+  low: number;
+  high: number;
+};
+
+/** Each player's chance of winning from a position in the game. */
+export type WinChances = {
+  /** Chance of winning for each player, by player index */
+  chances: Estimate[];
+  playouts: number;
+  /** Playouts that hit the run limit without a winner */
+  unfinished: number;
+};
+
+/** Summary of a saved game, to pick it out from the others. */
+export type SaveSummary = {
+  id: string;
+  name: string;
+  /** When the game was started, in seconds since the Unix epoch */
+  created: number;
+  /** When the game was last saved, in seconds since the Unix epoch */
+  updated: number;
+  players: Player[];
+  /** Runs played so far by all players */
+  runs: number;
+  /** Name of the winner, if the game is over */
+  winner: string | null;
+};
+
+/** Games played and won against some opponent. */
+export type WinRecord = {
+  played: number;
+  won: number;
+};
+
+/** How a player got on in one of their games. */
+export type CareerGame = {
+  /** When the game finished, in seconds since the Unix epoch */
+  finished: number;
+  won: boolean;
+  /** Share of runs that croaked (0.0 - 1.0) */
+  croak_rate: number;
+  luck: number;
+};
+
+/** A player's statistics over every game they've finished. */
+export type CareerStats = {
+  /** Name the player last played under */
+  name: string;
+  games_played: number;
+  games_won: number;
+  /** Record against each kind of bot, by mode */
+  vs_bots: Record<string, WinRecord>;
+  /** Average share of runs that croaked (0.0 - 1.0) */
+  croak_rate: number;
+  luck: number;
+  /** Each game, oldest first */
+  games: CareerGame[];
+  /** Columns (2 - 12) chosen most, with how often they were chosen */
+  favourite_columns: [number, number][];
+};
+
+/** A place on the leaderboard. */
+export type Standing = {
+  /** Profile id or name of the player, or key of the bot */
+  key: string;
+  name: string;
+  rating: number;
+  games: number;
+  bot: boolean;
+};
+
+/** How evenly matched a bot would be against a player. */
+export type BotMatch = {
+  mode: PlayerMode;
+  rating: number;
+  /** Player's expected score against the bot (0.0 - 1.0), 0.5 for an even game */
+  expected: number;
+};
+
+export type Achievement = "BothEnds" | "NervesOfSteel" | "InOneGo" | "Flawless";
+
+/** An achievement with what it's for. */
+export type AchievementInfo = {
+  achievement: Achievement;
+  title: string;
+  description: string;
+};
+
+/** An achievement a player has just unlocked. */
+export type Unlock = AchievementInfo & {
+  /** Index of the player who unlocked it */
+  player: number;
+};
+
+/** Achievements each player has unlocked, by profile id or name, with when (Unix seconds). */
+export type Achievements = Record<string, Partial<Record<Achievement, number>>>;
+
+/** A point in a game: after `turn` rolls of run `run`, both counting from 0. */
+export type ReplayPosition = {
+  run: number;
+  turn: number;
+};
+
+/** The game as it was at a point in a replay. */
+export type ReplayFrame = {
+  position: ReplayPosition;
+  /** Index of the player taking the run */
+  player: number;
+  /** Dice of the roll just made, null at the start of a run */
+  dice: [number, number, number, number] | null;
+  /** Columns (2 - 12) taken with the roll just made, null if it croaked */
+  chosen: PlayerChoice | null;
+  state: GameState;
+  /** Number of runs in the game, including the one waiting for its first roll */
+  runs: number;
+  /** Where stepping back or forward goes, null at the start or end of the game */
+  previous: ReplayPosition | null;
+  next: ReplayPosition | null;
 };